@@ -0,0 +1,180 @@
+// Translations of everything the game says. Every message lives in a catalog per language under
+// locales/, one "key = text" line each, with {name} where a value is filled in. Messages about a count,
+// like "3 guesses", have a "key.one" line for 1 and a "key.other" line for everything else.
+// The language comes from --lang, or else from LC_ALL, LC_MESSAGES or LANG, and falls back to English.
+
+// Imports
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// A language with a catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+// Every language there is a catalog for, English first since it has every message
+pub const LANGUAGES: [Language; 2] = [Language::English, Language::Spanish];
+
+impl Language {
+    // The code used to pick the language, e.g. with --lang es
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    // Finds the language of a code or locale name, e.g. "es", "es-MX" or "es_ES.UTF-8"
+    pub fn from_code(code: &str) -> Option<Language> {
+        let code = code.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+        LANGUAGES.iter().copied().find(|language| language.code() == code)
+    }
+
+    // The catalog file, built into the program
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.txt"),
+            Language::Spanish => include_str!("../locales/es.txt"),
+        }
+    }
+
+    // Finds a message in the catalog. The catalogs are small enough to read through every time.
+    fn message(self, key: &str) -> Option<&'static str> {
+        self.source().lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .find(|(name, _)| name.trim() == key)
+            .map(|(_, text)| text.trim())
+    }
+}
+
+// The language picked with --lang, or from the environment the first time a message is looked up
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+// Picks the language for the rest of the game, before anything is printed
+pub fn select(language: Language) {
+    let _ = LANGUAGE.set(language); // Only the first choice counts
+}
+
+// The language messages are in: the first of LC_ALL, LC_MESSAGES and LANG that is set decides
+fn language() -> Language {
+    *LANGUAGE.get_or_init(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_code(&value))
+            .unwrap_or(Language::English)
+    })
+}
+
+// Looks up a message and fills in its values, e.g. text("guess.prompt", &[("max", &100)])
+// A message missing from a catalog is shown in English rather than not at all
+pub fn text(key: &str, values: &[(&str, &dyn Display)]) -> String {
+    let template = language().message(key).or_else(|| Language::English.message(key)).unwrap_or(key);
+    fill(template, values)
+}
+
+// Replaces each {name} in a message with its value in one pass, so a value that happens to contain
+// "{name}" is never filled in again. Braces without a value are left as they are.
+fn fill(template: &str, values: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            values.iter().find(|(name, _)| *name == &rest[1..end]).map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            },
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            },
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+// Looks up the form of a message that fits the count, which is filled in as {count}
+pub fn plural(key: &str, count: usize, values: &[(&str, &dyn Display)]) -> String {
+    let mut values = values.to_vec();
+    values.push(("count", &count));
+    text(&format!("{}.{}", key, if count == 1 { "one" } else { "other" }), &values)
+}
+
+// Looks up a message: t!("guess.prompt", max = 100)
+macro_rules! t {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::text($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),*])
+    };
+}
+
+// Looks up a message about a count: tn!("count.guesses", 3) is "3 guesses"
+macro_rules! tn {
+    ($key:expr, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::plural($key, $count, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),*])
+    };
+}
+
+pub(crate) use {t, tn};
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The keys of a catalog, in order
+    fn keys(language: Language) -> Vec<&'static str> {
+        language.source().lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, _)| name.trim())
+            .collect()
+    }
+
+    // Every source file of the game, so no lookup goes unchecked
+    const SOURCES: [&str; 2] = [include_str!("main.rs"), include_str!("i18n.rs")];
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        for language in LANGUAGES {
+            assert_eq!(keys(language), keys(Language::English), "{} has different keys than en", language.code());
+
+            // Every message the game asks for exists, plural ones in both forms
+            for source in SOURCES {
+                let uses = source.match_indices("t!(\"").chain(source.match_indices("tn!(\""));
+                // Skip other macros ending the same way, like print!("
+                for (start, _) in uses.filter(|&(start, _)| !source[..start].ends_with(char::is_alphanumeric)) {
+                    let key = source[start..].split('"').nth(1).unwrap();
+                    let found = match source[start..].starts_with("tn!") {
+                        true => language.message(&format!("{}.one", key)).and(language.message(&format!("{}.other", key))),
+                        false => language.message(key),
+                    };
+                    assert!(found.is_some(), "{} is missing {}", language.code(), key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn languages_come_from_codes_and_locales() {
+        assert_eq!(Language::from_code("es_MX.UTF-8"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("en-GB"), Some(Language::English));
+        assert_eq!(Language::from_code("fr_FR"), None);
+    }
+
+    #[test]
+    fn values_are_filled_in_once() {
+        assert_eq!(fill("{a} and {b} and {c}", &[("b", &2), ("a", &1)]), "1 and 2 and {c}");
+        assert_eq!(fill("Hello {name}!", &[("name", &"{name}"), ("other", &"x")]), "Hello {name}!");
+        assert_eq!(fill("{", &[]), "{");
+    }
+}
@@ -1,11 +1,16 @@
 // Example 2: Flow and Enums
 
+// Modules
+mod i18n;
+
 // Imports
 use std::env;
 use std::io::{self};
+use std::process;
 use rand::prelude::*;
 
 use std::cmp::{Ordering};
+use i18n::{t, tn, Language, LANGUAGES};
 
 // Constants
 const MAX_RANGE: u32 = 100;
@@ -42,29 +47,32 @@ fn judge_guess(guess: u32, actual: u32) -> GuessEnum {
 }
 
 // Wrap the game loop 
-fn game_loop(actual: u32, baby_mode: bool) -> Result<u32, String> {
+// Returns the number of guesses, or None if the input ran out first
+fn game_loop(actual: u32, baby_mode: bool) -> Result<Option<u32>, String> {
     // Counter
     let mut num_guesses = 1;
 
     // Loops infinitely until broken
     loop {
-        println!("Please enter a number from 1 to {}:", MAX_RANGE);
+        println!("{}", t!("guess.prompt", max = MAX_RANGE));
 
         // Get user input
         let mut guess_line = String::new();
         let result = io::stdin().read_line(&mut guess_line);
         let guess_str = guess_line.trim();
 
-        // Catch error in obtaining user input
-        if result.is_err() {
-            return Err(format!("Could not read STDIN. Error: {}", result.unwrap_err()));
+        // Catch error in obtaining user input, and stop the round if stdin closed (Ctrl-D, or a piped file ran out)
+        match result {
+            Ok(0) => return Ok(None),
+            Ok(_) => {},
+            Err(e) => return Err(t!("error.stdin", error = e)),
         }
 
         // If a valid input is parsed, assign it to guess, otherwise, ask user to try again.
         let guess = if let Some(num) = parse_int(guess_str) {
             num
         } else {
-            println!("Please enter a valid number!");
+            println!("{}", t!("guess.invalid"));
             continue; // Don't count a guess if it's not valid
         };
 
@@ -72,18 +80,18 @@ fn game_loop(actual: u32, baby_mode: bool) -> Result<u32, String> {
         match judge_guess(guess, actual) {
             GuessEnum::TooHigh(diff) => {
                 if baby_mode {
-                    println!("Oh no thats too high. You were off by {}", diff);
+                    println!("{}", t!("guess.too_high_by", difference = diff));
                 } else {
-                    println!("Oh no thats too high.");
+                    println!("{}", t!("guess.too_high"));
                 }
             },
             GuessEnum::TooLow(diff) => {
                 match baby_mode {
-                    true => println!("Oh no thats too low. You were off by {}", diff),
-                    false => println!("Oh no thats too low."),
+                    true => println!("{}", t!("guess.too_low_by", difference = diff)),
+                    false => println!("{}", t!("guess.too_low")),
                 }
             },
-            GuessEnum::Exact => return Ok(num_guesses),
+            GuessEnum::Exact => return Ok(Some(num_guesses)),
         }
 
         num_guesses += 1; // increment counter
@@ -92,13 +100,34 @@ fn game_loop(actual: u32, baby_mode: bool) -> Result<u32, String> {
 
 fn main() {
     // Parse command args
-    let baby_mode = env::args().any(|i| i.trim() == "--baby"); // The program will alert the player how far off they are
+    let args: Vec<String> = env::args().collect();
+    let baby_mode = args.iter().any(|i| i.trim() == "--baby"); // The program will alert the player how far off they are
+
+    // Pick the language, e.g. --lang es. Without it the language comes from LANG.
+    if let Some(position) = args.iter().position(|i| i.trim() == "--lang") {
+        let language = match args.get(position + 1) {
+            Some(code) => Language::from_code(code).ok_or_else(|| {
+                let codes: Vec<&str> = LANGUAGES.iter().map(|language| language.code()).collect();
+                t!("error.language", language = code, expected = codes.join(", "))
+            }),
+            None => Err(t!("error.missing_language")),
+        };
+        match language {
+            Ok(language) => i18n::select(language),
+            Err(e) => {
+                println!("{}", e);
+                process::exit(1);
+            },
+        }
+    }
+
+    let mut total_guesses = 0;
 
     // Play a new game for each round
     for i in 0..MAX_ROUNDS {
         // Preamble text
-        println!("Starting round {} of {}", i + 1, MAX_ROUNDS);
-        println!("I'm thinking of a number from 0 to {}", MAX_RANGE);
+        println!("{}", t!("round.start", round = i + 1, rounds = MAX_ROUNDS));
+        println!("{}", t!("round.thinking", max = MAX_RANGE));
 
         // Generate the random numer
         let mut rng = rand::thread_rng();
@@ -106,11 +135,21 @@ fn main() {
 
         // Catches exceptions in the main game loop
         match game_loop(actual, baby_mode) {
-            Ok(num_guesses) => println!("Wow you got it in only {} guesses!\n", num_guesses),
-            Err(err) => println!("Hmmm found an error: {}\n", err),
-        };
+            Ok(Some(num_guesses)) => {
+                println!("{}\n", tn!("guess.exact", num_guesses as usize));
+                total_guesses += num_guesses;
+            },
+            Ok(None) => {
+                // The input ran out: say how far the player got, and what the number was
+                println!("\n{}", t!("stop.round", round = i + 1, rounds = MAX_ROUNDS, number = actual));
+                let (rounds, guesses) = (tn!("count.rounds", i as usize), tn!("count.guesses", total_guesses as usize));
+                println!("{}", t!("stop.summary", rounds = rounds, guesses = guesses));
+                process::exit(1);
+            },
+            Err(err) => println!("{}\n", t!("error.found", error = err)),
+        }
     }
 
     // Post-game
-    println!("Thank you for playing!");
+    println!("{}", t!("goodbye"));
 }
@@ -0,0 +1,37 @@
+// Integration tests for playing the guessing game in another language
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Plays with the given arguments, locale and input, then closes stdin
+fn run(args: &[&str], lang: &str, input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_flow-enums"))
+        .args(args)
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env("LANG", lang)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the game starts");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn spanish_from_lang_flag_or_environment() {
+    for (args, lang) in [(&["--lang", "es"][..], "en_US.UTF-8"), (&[][..], "es_MX.UTF-8")] {
+        let output = run(args, lang, "101\n");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Empieza la ronda 1 de 3"), "{}", stdout);
+        assert!(stdout.contains("Oh no, demasiado alto."), "{}", stdout);
+        assert!(stdout.contains("Terminaste 0 rondas con 0 intentos."), "{}", stdout);
+    }
+}
+
+#[test]
+fn unknown_languages_are_rejected() {
+    let output = run(&["--lang", "xx"], "", "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Unknown language 'xx', expected one of: en, es"));
+}
@@ -0,0 +1,43 @@
+// Integration tests for stopping the guessing game early: input that runs out mid-game
+
+use std::io::Write;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Starts the game with its stdin and stdout piped
+fn spawn() -> Child {
+    Command::new(env!("CARGO_BIN_EXE_flow-enums"))
+        .env("LC_ALL", "C") // The messages checked are the English ones
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the game starts")
+}
+
+// Waits for the game to exit, failing the test instead of hanging if it never does
+fn finish(mut child: Child) -> Output {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while child.try_wait().unwrap().is_none() {
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            panic!("the game kept running after its input ended");
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn input_ending_stops_the_game() {
+    for input in &["", "50\n", "50\nnot a number\n"] {
+        let mut child = spawn();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        let output = finish(child);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(output.status.code(), Some(1), "{}", stdout);
+        assert!(stdout.contains("Stopping in round 1 of 3."), "{}", stdout);
+        assert!(stdout.contains("You finished 0 rounds with 0 guesses."), "{}", stdout);
+    }
+}
@@ -0,0 +1,22 @@
+// Any board string either sets up a game whose board string reads back as the same game, or is rejected
+// without panicking
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tic_tac_toe::{GameBuilder, Variant};
+
+fuzz_target!(|input: (bool, &str)| {
+    let (order_chaos, board) = input;
+    let variant = if order_chaos { Variant::OrderAndChaos } else { Variant::Standard };
+
+    let game = match GameBuilder::new().variant(variant).board_string(board).and_then(GameBuilder::build) {
+        Ok(game) => game,
+        Err(_) => return,
+    };
+
+    let written = game.to_board_string();
+    let reread = GameBuilder::new().variant(variant).board_string(&written).and_then(GameBuilder::build)
+        .expect("a game's own board string is valid");
+    assert_eq!(reread.to_board_string(), written);
+    assert_eq!(reread.winner(), game.winner());
+});
@@ -0,0 +1,24 @@
+// Any input either parses to a tile on the board, written back the same way, or is rejected without panicking
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tic_tac_toe::{parse_move, tile_name, Players};
+
+fuzz_target!(|input: (u8, u8, &str)| {
+    let (size, player_count, text) = input;
+    let size = usize::from(size % 26) + 1;
+    let players = match Players::new(usize::from(player_count % 7) + 2) {
+        Ok(players) => players,
+        Err(_) => return,
+    };
+
+    if let Ok((row, col, piece)) = parse_move(text, size, &players) {
+        assert!(row < size && col < size, "{:?} parsed off a {}x{} board", text, size, size);
+        if let Some(piece) = piece {
+            assert!(piece.index() < players.count());
+        }
+
+        // The standard name of the tile parses back to the same tile
+        assert_eq!(parse_move(&tile_name(row, col), size, &players).map(|(row, col, _)| (row, col)), Ok((row, col)));
+    }
+});
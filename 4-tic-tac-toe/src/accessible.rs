@@ -0,0 +1,33 @@
+// Output for screen readers, turned on with --accessible or by setting TTT_ACCESSIBLE (to anything but 0).
+// Boards are described row by row in words instead of drawn, moves and results are announced in full
+// sentences, and prompts end their line instead of leaving the cursor after them.
+
+// Standard Library Import Statements
+use std::env;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by --accessible, for the rest of the program
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Turns accessible output on
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// Whether output should be accessible, from --accessible or the environment
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || env::var("TTT_ACCESSIBLE").is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+// Asks for input, e.g. prompt("Enter move (e.g. 1A):")
+// Screen readers read a prompt reliably only once its line is finished, so accessible prompts end theirs
+pub fn prompt(text: &str) -> io::Result<()> {
+    if is_enabled() {
+        println!("{}", text);
+        Ok(())
+    } else {
+        print!("{} ", text);
+        io::stdout().flush()
+    }
+}
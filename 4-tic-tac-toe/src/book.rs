@@ -0,0 +1,468 @@
+// An opening book: the best moves for every position near the start of a game, worked out ahead of time
+// Positions are stored once per symmetry (see symmetry.rs) in a compact binary file.
+//
+// File layout (numbers are little endian):
+//   magic         4 bytes  "TTTB"
+//   format        u8       BOOK_FORMAT_VERSION
+//   rules         u8       RULES_VERSION
+//   variant       u8       0 = standard, 1 = Order and Chaos
+//   size          u8       board width and height
+//   win length    u8       pieces in a row needed to win
+//   depth         u8       moves from the start the book covers
+//   entries       u32      number of entries that follow
+// Each entry:
+//   tiles         2 bits per tile, row by row (0 = empty, 1 = x, 2 = o), padded to a whole byte
+//   side          u8       current piece index, plus 2 when Chaos is to move
+//   outcome       u8       top 2 bits: 0 = draw, 1 = win, 2 = loss; low 6 bits: moves until the end
+//   move count    u8
+//   moves         u16 each (tile index * 2 + piece index), in the canonical orientation
+
+// Standard Library Import Statements
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, Move, Piece, Role, Variant};
+use crate::i18n::t;
+use crate::messages::variant_name;
+use crate::solver::{Outcome, SolveError, Solver};
+use crate::symmetry;
+
+// Marks the start of a book file
+const MAGIC: &[u8; 4] = b"TTTB";
+
+// Bump when the file layout changes
+const BOOK_FORMAT_VERSION: u8 = 1;
+
+// Bump when the rules in game.rs change in a way that changes who wins, so old books are rejected
+pub const RULES_VERSION: u8 = 1;
+
+// The outcome byte only has room for this many moves
+const MAX_OUTCOME_MOVES: usize = 0b0011_1111;
+
+// Define errors in reading or writing a book
+#[derive(Debug)]
+pub enum BookError {
+    Io(io::Error),
+    NotABook, // The file doesn't start with the magic bytes
+    UnsupportedFormat { version: u8 }, // Written by a different version of this program
+    Stale { reason: String }, // Built for different rules than the game being played
+    Corrupt, // The file ended early or has values out of range
+    Unsupported, // Books only cover two player games on boards up to 7x7
+    Solve(SolveError),
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::Io(err) => write!(f, "{}", err),
+            BookError::NotABook => write!(f, "{}", t!("book_error.not_a_book")),
+            BookError::UnsupportedFormat {version} => {
+                write!(f, "{}", t!("book_error.format", version = version, expected = BOOK_FORMAT_VERSION))
+            },
+            BookError::Stale {reason} => write!(f, "{}", t!("book_error.stale", reason = reason)),
+            BookError::Corrupt => write!(f, "{}", t!("book_error.corrupt")),
+            BookError::Unsupported => write!(f, "{}", t!("book_error.unsupported")),
+            BookError::Solve(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<io::Error> for BookError {
+    fn from(err: io::Error) -> Self {
+        BookError::Io(err)
+    }
+}
+
+impl From<SolveError> for BookError {
+    fn from(err: SolveError) -> Self {
+        BookError::Solve(err)
+    }
+}
+
+// The best moves in one position and where they lead
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    outcome: Outcome,
+    moves: Vec<Move>, // In the canonical orientation
+}
+
+// Define the Book
+#[derive(Debug, Clone)]
+pub struct Book {
+    rules_version: u8,
+    variant: Variant,
+    size: usize,
+    win_length: usize,
+    depth: usize,
+    entries: HashMap<Vec<u8>, Entry>,
+}
+
+impl Book {
+    // Builds a book by solving every position up to `depth` moves after `start`
+    pub fn build(start: &Game, depth: usize, solver: &mut Solver) -> Result<Self, BookError> {
+        let size = start.tiles().len();
+        if start.players().count() != 2 || size * size > MAX_OUTCOME_MOVES {
+            return Err(BookError::Unsupported);
+        }
+
+        let mut book = Self {
+            rules_version: RULES_VERSION,
+            variant: start.variant(),
+            size,
+            win_length: start.win_length(),
+            depth,
+            entries: HashMap::new(),
+        };
+
+        // Remember how deep each position was explored, a position can be reached again sooner
+        let mut explored = HashMap::new();
+        book.add_positions(start, depth, solver, &mut explored)?;
+        Ok(book)
+    }
+
+    // Adds a position and everything reachable from it within `depth` moves
+    fn add_positions(
+        &mut self,
+        game: &Game,
+        depth: usize,
+        solver: &mut Solver,
+        explored: &mut HashMap<Vec<u8>, usize>,
+    ) -> Result<(), BookError> {
+        if game.is_finished() {
+            return Ok(());
+        }
+
+        let (key, t) = position_key(game);
+        if explored.get(&key).is_some_and(|&explored_depth| explored_depth >= depth) {
+            return Ok(());
+        }
+        explored.insert(key.clone(), depth);
+
+        // Keep every move that is as good as the best one
+        if !self.entries.contains_key(&key) {
+            let moves = solver.evaluate_moves(game)?;
+            let best = moves.iter().map(|&(_, outcome)| outcome.score()).max()
+                .expect("unfinished games always have a legal move");
+            let best_moves: Vec<(Move, Outcome)> = moves.into_iter()
+                .filter(|&(_, outcome)| outcome.score() == best)
+                .collect();
+
+            let entry = Entry {
+                outcome: best_moves[0].1,
+                moves: best_moves.iter().map(|&(mv, _)| symmetry::transform_move(t, mv, self.size)).collect(),
+            };
+            self.entries.insert(key, entry);
+        }
+
+        // Carry on to the positions after every legal move
+        if depth > 0 {
+            for mv in game.legal_moves() {
+                let mut next = game.clone();
+                next.play(mv).expect("legal moves can always be played");
+                self.add_positions(&next, depth - 1, solver, explored)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Checks the book was built for the same rules as the game
+    pub fn check_rules(&self, game: &Game) -> Result<(), BookError> {
+        let stale = |reason: String| Err(BookError::Stale {reason});
+
+        if self.rules_version != RULES_VERSION {
+            stale(t!("book_stale.rules", built = self.rules_version, expected = RULES_VERSION))
+        }
+        else if self.variant != game.variant() {
+            stale(t!("book_stale.variant", built = variant_name(self.variant), expected = variant_name(game.variant())))
+        }
+        else if self.size != game.tiles().len() {
+            stale(t!("book_stale.size", built = self.size, expected = game.tiles().len()))
+        }
+        else if self.win_length != game.win_length() {
+            stale(t!("book_stale.win_length", built = self.win_length, expected = game.win_length()))
+        }
+        else if game.players().count() != 2 {
+            stale(t!("book_stale.players", count = game.players().count()))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    // Finds the best moves for the current player and where they lead, if the position is in the book
+    // Moves the rules don't allow are left out, since the book is only a file anyone could have edited.
+    // If none are left the position counts as missing, so the caller searches it instead.
+    pub fn lookup(&self, game: &Game) -> Option<(Vec<Move>, Outcome)> {
+        if game.is_finished() || self.check_rules(game).is_err() {
+            return None;
+        }
+
+        // Turn the stored moves back to the way the board is actually facing
+        let (key, t) = position_key(game);
+        let entry = self.entries.get(&key)?;
+        let legal = game.legal_moves();
+        let moves: Vec<Move> = entry.moves.iter()
+            .map(|&mv| symmetry::transform_move(symmetry::inverse(t), mv, self.size))
+            .filter(|mv| legal.contains(mv))
+            .collect();
+        match moves.is_empty() {
+            true => None,
+            false => Some((moves, entry.outcome)),
+        }
+    }
+
+    // Number of positions in the book
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    // Saves the book in the binary format described at the top of this file
+    pub fn write_to(&self, path: &Path) -> Result<(), BookError> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(BOOK_FORMAT_VERSION);
+        bytes.push(self.rules_version);
+        bytes.push(match self.variant {
+            Variant::Standard => 0,
+            Variant::OrderAndChaos => 1,
+        });
+        bytes.push(self.size as u8);
+        bytes.push(self.win_length as u8);
+        bytes.push(self.depth.min(u8::MAX as usize) as u8);
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        // Sort the entries so the same book always makes the same file
+        let mut keys: Vec<&Vec<u8>> = self.entries.keys().collect();
+        keys.sort();
+        for key in keys {
+            let entry = &self.entries[key];
+            let (cells, side) = key.split_at(key.len() - 1);
+            bytes.extend(pack_cells(cells));
+            bytes.push(side[0]);
+            bytes.push(encode_outcome(entry.outcome).ok_or(BookError::Unsupported)?);
+            bytes.push(entry.moves.len() as u8);
+            for mv in &entry.moves {
+                let code = ((mv.row * self.size + mv.col) * 2 + mv.piece.index()) as u16;
+                bytes.extend_from_slice(&code.to_le_bytes());
+            }
+        }
+
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    // Loads a book saved by write_to
+    pub fn read_from(path: &Path) -> Result<Self, BookError> {
+        let bytes = fs::read(path)?;
+        let mut reader = Reader {bytes: &bytes, pos: 0};
+
+        // Check the header before trusting anything else
+        if reader.take(4)? != MAGIC {
+            return Err(BookError::NotABook);
+        }
+        let version = reader.byte()?;
+        if version != BOOK_FORMAT_VERSION {
+            return Err(BookError::UnsupportedFormat {version});
+        }
+        let rules_version = reader.byte()?;
+        let variant = match reader.byte()? {
+            0 => Variant::Standard,
+            1 => Variant::OrderAndChaos,
+            _ => return Err(BookError::Corrupt),
+        };
+        let size = reader.byte()? as usize;
+        let win_length = reader.byte()? as usize;
+        let depth = reader.byte()? as usize;
+        let count = u32::from_le_bytes(reader.take(4)?.try_into().expect("took 4 bytes"));
+        if size == 0 || size * size > MAX_OUTCOME_MOVES {
+            return Err(BookError::Corrupt);
+        }
+
+        // Read each entry back into a key and the best moves
+        let mut entries = HashMap::new();
+        let tiles = size * size;
+        for _ in 0..count {
+            let mut key = unpack_cells(reader.take(tiles.div_ceil(4))?, tiles);
+            let side = reader.byte()?;
+            let outcome = decode_outcome(reader.byte()?).ok_or(BookError::Corrupt)?;
+            let move_count = reader.byte()?;
+            // Tiles are empty, x or o, and only Order and Chaos has a role to move besides the piece
+            let max_side = if variant == Variant::OrderAndChaos { 3 } else { 1 };
+            if key.iter().any(|&cell| cell > 2) || side > max_side || move_count == 0 {
+                return Err(BookError::Corrupt);
+            }
+
+            // Every move has to go on an empty tile, with the piece to move unless either piece may be placed
+            let mut moves = Vec::new();
+            for _ in 0..move_count {
+                let code = u16::from_le_bytes(reader.take(2)?.try_into().expect("took 2 bytes")) as usize;
+                let (tile, piece) = (code / 2, code % 2);
+                let wrong_piece = variant == Variant::Standard && piece != side as usize;
+                if tile >= tiles || key[tile] != 0 || wrong_piece {
+                    return Err(BookError::Corrupt);
+                }
+                moves.push(Move {row: tile / size, col: tile % size, piece: Piece::new(piece)});
+            }
+            key.push(side);
+            entries.insert(key, Entry {outcome, moves});
+        }
+
+        Ok(Self {rules_version, variant, size, win_length, depth, entries})
+    }
+}
+
+// Walks through the bytes of a book file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    // Takes the next `len` bytes, or fails if the file ends first
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BookError> {
+        let slice = self.bytes.get(self.pos..self.pos + len).ok_or(BookError::Corrupt)?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, BookError> {
+        Ok(self.take(1)?[0])
+    }
+}
+
+// Finds the canonical key of a position (its smallest rotation or reflection plus the side to move),
+// and the transform that turns the board into that orientation
+fn position_key(game: &Game) -> (Vec<u8>, usize) {
+    let (mut key, t) = symmetry::canonical_cells(game.tiles());
+    let chaos = if game.current_role() == Some(Role::Chaos) { 2 } else { 0 };
+    key.push(game.current_piece().index() as u8 + chaos);
+    (key, t)
+}
+
+// Packs tile values (0-2) four to a byte
+fn pack_cells(cells: &[u8]) -> Vec<u8> {
+    cells.chunks(4)
+        .map(|chunk| chunk.iter().enumerate().fold(0, |byte, (i, &cell)| byte | cell << (i * 2)))
+        .collect()
+}
+
+// Unpacks `count` tile values packed by pack_cells
+fn unpack_cells(bytes: &[u8], count: usize) -> Vec<u8> {
+    (0..count).map(|i| (bytes[i / 4] >> ((i % 4) * 2)) & 0b11).collect()
+}
+
+// Packs an outcome into one byte, if the move count fits
+pub(crate) fn encode_outcome(outcome: Outcome) -> Option<u8> {
+    let (kind, moves) = match outcome {
+        Outcome::Draw => (0, 0),
+        Outcome::Win(moves) => (1, moves),
+        Outcome::Loss(moves) => (2, moves),
+    };
+    if moves > MAX_OUTCOME_MOVES {
+        return None;
+    }
+    Some(kind << 6 | moves as u8)
+}
+
+// Unpacks an outcome packed by encode_outcome
+pub(crate) fn decode_outcome(byte: u8) -> Option<Outcome> {
+    let moves = (byte & MAX_OUTCOME_MOVES as u8) as usize;
+    match byte >> 6 {
+        0 => Some(Outcome::Draw),
+        1 => Some(Outcome::Win(moves)),
+        2 => Some(Outcome::Loss(moves)),
+        _ => None,
+    }
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::Bot;
+    use tic_tac_toe::game::GameBuilder;
+
+    // A book file no other test uses
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("tic-tac-toe-book-{}-{}", std::process::id(), name))
+    }
+
+    // The byte where the first entry (the empty 3x3 board, x to move) starts, just after the header
+    const FIRST_ENTRY: usize = 14;
+
+    // A depth 1 book for 3x3, written to disk
+    fn book_file(name: &str) -> (Book, std::path::PathBuf, Vec<u8>) {
+        let book = Book::build(&Game::new(), 1, &mut Solver::new()).unwrap();
+        let path = temp_path(name);
+        book.write_to(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        (book, path, bytes)
+    }
+
+    #[test]
+    fn books_survive_a_round_trip() {
+        let (book, path, _) = book_file("round-trip.book");
+        let read = Book::read_from(&path).unwrap();
+        assert_eq!(read.entries, book.entries);
+
+        let mut game = Game::new();
+        game.make_move(0, 0).unwrap();
+        let (moves, outcome) = read.lookup(&game).unwrap();
+        assert_eq!(outcome, Outcome::Draw);
+        assert_eq!(moves, vec![Move {row: 1, col: 1, piece: game.current_piece()}]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn bad_entries_are_rejected_when_reading() {
+        let (_, path, bytes) = book_file("bad-entries.book");
+        let corruptions: [fn(&mut Vec<u8>); 4] = [
+            |bytes| bytes[FIRST_ENTRY] = 0b01,          // x on 1A, where the stored moves play too
+            |bytes| bytes[FIRST_ENTRY] = 0b11,          // A tile that is neither empty, x nor o
+            |bytes| bytes[FIRST_ENTRY + 3] = 1,         // o to move on an empty board, but the moves place x
+            |bytes| bytes[FIRST_ENTRY + 5] = 0,         // No moves at all
+        ];
+        for corrupt in corruptions {
+            let mut bytes = bytes.clone();
+            corrupt(&mut bytes);
+            fs::write(&path, bytes).unwrap();
+            assert!(matches!(Book::read_from(&path), Err(BookError::Corrupt)));
+        }
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn illegal_moves_in_a_book_fall_back_to_the_solver() {
+        let mut book = Book::build(&Game::new(), 1, &mut Solver::new()).unwrap();
+        let mut game = Game::new();
+        game.make_move(0, 0).unwrap();
+
+        // Point the position's entry at the tile x already took
+        let (key, t) = position_key(&game);
+        let taken = symmetry::transform_move(t, Move {row: 0, col: 0, piece: game.current_piece()}, 3);
+        book.entries.get_mut(&key).unwrap().moves = vec![taken];
+        assert_eq!(book.lookup(&game), None);
+
+        let (mv, _) = Bot::with_book(book).choose_move(&game).unwrap();
+        assert!(game.legal_moves().contains(&mv));
+    }
+
+    #[test]
+    fn books_for_other_rules_are_stale() {
+        let book = Book::build(&Game::new(), 0, &mut Solver::new()).unwrap();
+        let bigger = GameBuilder::new().size(4).build().unwrap();
+        let err = book.check_rules(&bigger).unwrap_err();
+        assert!(matches!(err, BookError::Stale {..}));
+        assert!(err.to_string().contains("3x3"), "{}", err);
+        assert_eq!(book.lookup(&bigger), None);
+    }
+}
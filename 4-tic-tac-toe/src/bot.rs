@@ -0,0 +1,44 @@
+// A computer player: looks the position up in an opening book first, and searches with the solver otherwise
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, Move};
+use crate::book::Book;
+use crate::solver::{Outcome, SolveError, Solver};
+
+// Define the Bot
+#[derive(Debug, Clone)]
+pub struct Bot {
+    book: Option<Book>,
+    solver: Solver,
+}
+
+impl Bot {
+    // Constructor for a bot that always searches
+    pub fn new() -> Self {
+        Self {
+            book: None,
+            solver: Solver::new(),
+        }
+    }
+
+    // Constructor for a bot that checks a book before searching
+    pub fn with_book(book: Book) -> Self {
+        Self {
+            book: Some(book),
+            solver: Solver::new(),
+        }
+    }
+
+    // Picks the best move for the current player and the result it leads to
+    pub fn choose_move(&mut self, game: &Game) -> Result<(Move, Outcome), SolveError> {
+        if let Some((moves, outcome)) = self.book.as_ref().and_then(|book| book.lookup(game)) {
+            return Ok((moves[0], outcome));
+        }
+        self.solver.best_move(game)
+    }
+
+    // The solver behind the bot, for questions the book can't answer
+    pub fn solver(&mut self) -> &mut Solver {
+        &mut self.solver
+    }
+}
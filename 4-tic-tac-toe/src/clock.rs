@@ -0,0 +1,258 @@
+// Chess clocks: every side has a time budget that runs down during their turns
+//
+// A side is the piece to move in a standard game, and the role in Order and Chaos (0 for Order, 1 for Chaos).
+// Frontends that seat players differently, like a match where Order and Chaos swap roles, can number the
+// sides themselves and drive the clocks with start and stop.
+
+// Standard Library Import Statements
+use std::time::{Duration, Instant};
+
+// Package Import Statements
+use crate::game::{Game, Role, Variant};
+
+/// Remaining time below which players get a warning
+pub const LOW_TIME: Duration = Duration::from_secs(10);
+
+/// How much time each side gets, e.g. "30+2" is 30 seconds plus 2 more after every move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub initial: Duration,
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    /// Parses "<seconds>" or "<seconds>+<increment seconds>", None if it isn't a time control
+    pub fn parse(input: &str) -> Option<Self> {
+        let seconds = |text: &str| {
+            text.trim().parse::<f64>().ok()
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .map(Duration::from_secs_f64)
+        };
+
+        let (initial, increment) = match input.split_once('+') {
+            Some((initial, increment)) => (seconds(initial)?, seconds(increment)?),
+            None => (seconds(input)?, Duration::ZERO),
+        };
+        if initial.is_zero() {
+            return None;
+        }
+        Some(Self {initial, increment})
+    }
+}
+
+/// The clocks of every side of a game
+#[derive(Debug, Clone)]
+pub struct Clocks {
+    control: TimeControl,
+    remaining: Vec<Duration>,
+    running: Option<(usize, Instant)>, // The side whose clock is running, and since when
+}
+
+impl Clocks {
+    /// Constructor for `sides` clocks that all start with the full time, none of them running
+    pub fn new(control: TimeControl, sides: usize) -> Self {
+        Self {
+            control,
+            remaining: vec![control.initial; sides],
+            running: None,
+        }
+    }
+
+    /// Constructor for a clock per side of `game`, with the clock of the side to move already running
+    pub fn for_game(control: TimeControl, game: &Game) -> Self {
+        let sides = match game.variant() {
+            Variant::Standard => game.players().count(),
+            Variant::OrderAndChaos => 2,
+        };
+        let mut clocks = Self::new(control, sides);
+        if !game.is_finished() {
+            clocks.start(side_to_move(game));
+        }
+        clocks
+    }
+
+    /// Starts a side's clock, stopping whichever one was running without adding the increment
+    pub fn start(&mut self, side: usize) {
+        self.pause();
+        self.running = Some((side, Instant::now()));
+    }
+
+    /// Stops the running clock after a move. Adds the increment unless the side already ran out.
+    /// Returns whether the side still had time left.
+    pub fn stop(&mut self) -> bool {
+        let side = match self.running {
+            Some((side, _)) => side,
+            None => return true,
+        };
+        self.pause();
+
+        let in_time = !self.remaining[side].is_zero();
+        if in_time {
+            self.remaining[side] += self.control.increment;
+        }
+        in_time
+    }
+
+    /// Hands the turn over after a move was played on `game`: stops the running clock, adding the increment,
+    /// and starts the clock of the side to move now. Once the game is over every clock stays stopped.
+    pub fn switch(&mut self, game: &Game) {
+        self.stop();
+        if !game.is_finished() {
+            self.start(side_to_move(game));
+        }
+    }
+
+    /// Calls Game::time_out on `game` if the side to move has run out of time, and starts the clock of the
+    /// next side if the game goes on (with three or more players). Returns whether anyone ran out.
+    pub fn check(&mut self, game: &mut Game) -> bool {
+        if game.is_finished() || !self.is_flagged(side_to_move(game)) {
+            return false;
+        }
+        self.pause();
+        game.time_out().expect("the game was still going");
+        if !game.is_finished() {
+            self.start(side_to_move(game));
+        }
+        true
+    }
+
+    /// Time a side has left, counting the turn in progress
+    pub fn remaining(&self, side: usize) -> Duration {
+        match self.running {
+            Some((running, since)) if running == side => self.remaining[side].saturating_sub(since.elapsed()),
+            _ => self.remaining[side],
+        }
+    }
+
+    /// Checks if a side has run out of time
+    pub fn is_flagged(&self, side: usize) -> bool {
+        self.remaining(side).is_zero()
+    }
+
+    /// The side whose clock is running, if any
+    pub fn running(&self) -> Option<usize> {
+        self.running.map(|(side, _)| side)
+    }
+
+    /// How many sides have a clock
+    pub fn sides(&self) -> usize {
+        self.remaining.len()
+    }
+
+    // Stops the running clock, charging it for the time used
+    fn pause(&mut self) {
+        if let Some((side, since)) = self.running.take() {
+            self.remaining[side] = self.remaining[side].saturating_sub(since.elapsed());
+        }
+    }
+}
+
+/// The side whose clock runs while `game` waits for a move: the piece to move, or the role in Order and Chaos
+pub fn side_to_move(game: &Game) -> usize {
+    match game.current_role() {
+        Some(Role::Order) => 0,
+        Some(Role::Chaos) => 1,
+        None => game.current_piece().index(),
+    }
+}
+
+/// Formats a duration as minutes, seconds and tenths, e.g. "0:29.4"
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Piece, Players, Winner};
+
+    fn control(initial: u64, increment: u64) -> TimeControl {
+        TimeControl {initial: Duration::from_secs(initial), increment: Duration::from_secs(increment)}
+    }
+
+    #[test]
+    fn time_controls_parse() {
+        assert_eq!(TimeControl::parse("30+2"), Some(control(30, 2)));
+        assert_eq!(TimeControl::parse(" 30 "), Some(control(30, 0)));
+        assert_eq!(TimeControl::parse("0.5").map(|control| control.initial), Some(Duration::from_millis(500)));
+        for invalid in ["", "0", "0+5", "-1", "30+", "+2", "inf", "NaN", "thirty"] {
+            assert_eq!(TimeControl::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn moving_in_time_adds_the_increment() {
+        let mut game = Game::new();
+        let mut clocks = Clocks::for_game(control(30, 2), &game);
+        assert_eq!(clocks.running(), Some(0));
+
+        game.make_move(1, 1).unwrap();
+        clocks.switch(&game);
+        assert_eq!(clocks.running(), Some(1));
+        assert!(clocks.remaining(0) > Duration::from_secs(31));
+        assert!(!clocks.check(&mut game));
+
+        // Once the game is over nobody's clock runs
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 2)] {
+            game.make_move(row, col).unwrap();
+            clocks.switch(&game);
+        }
+        assert!(game.is_finished());
+        assert_eq!(clocks.running(), None);
+    }
+
+    #[test]
+    fn running_out_of_time_ends_the_game() {
+        let mut game = Game::new();
+        let mut clocks = Clocks::for_game(control(30, 0), &game);
+        game.make_move(1, 1).unwrap();
+        clocks.switch(&game);
+
+        // o takes all of their time, so x wins
+        clocks.remaining[1] = Duration::ZERO;
+        assert!(clocks.is_flagged(1));
+        assert!(clocks.check(&mut game));
+        assert_eq!(game.winner(), Some(Winner::Player(Piece::X)));
+        assert_eq!(clocks.running(), None);
+        assert!(clocks.is_flagged(1));
+        assert!(!clocks.check(&mut game), "the game only ends once");
+    }
+
+    #[test]
+    fn running_out_of_time_in_order_and_chaos() {
+        let mut game = Game::with_rules(Variant::OrderAndChaos, 6, 5, Players::new(2).unwrap()).unwrap();
+        let mut clocks = Clocks::for_game(control(30, 0), &game);
+        assert_eq!((clocks.sides(), clocks.running()), (2, Some(0)));
+
+        clocks.remaining[0] = Duration::ZERO;
+        assert!(clocks.check(&mut game));
+        assert_eq!(game.winner(), Some(Winner::Chaos));
+    }
+
+    #[test]
+    fn running_out_of_time_with_three_players_skips_the_player() {
+        let mut game = Game::with_rules(Variant::Standard, 4, 3, Players::new(3).unwrap()).unwrap();
+        let mut clocks = Clocks::for_game(control(30, 0), &game);
+        assert_eq!(clocks.sides(), 3);
+
+        // x is out, so o moves and o's clock runs
+        clocks.remaining[0] = Duration::ZERO;
+        assert!(clocks.check(&mut game));
+        assert_eq!(game.winner(), None);
+        assert_eq!((game.current_piece(), clocks.running()), (Piece::O, Some(1)));
+
+        // Once o is out as well, the last player in time wins
+        clocks.remaining[1] = Duration::ZERO;
+        assert!(clocks.check(&mut game));
+        assert_eq!(game.winner(), Some(Winner::Player(Piece::new(2))));
+        assert_eq!(clocks.running(), None);
+    }
+
+    #[test]
+    fn times_are_formatted_to_the_tenth() {
+        assert_eq!(format_time(Duration::from_millis(29_460)), "0:29.4");
+        assert_eq!(format_time(Duration::from_secs(125)), "2:05.0");
+    }
+}
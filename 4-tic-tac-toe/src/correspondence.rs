@@ -0,0 +1,284 @@
+// Correspondence games: the whole game lives in a file that the players take turns updating,
+// e.g. in a shared folder or a directory synced between their computers
+//
+// The file is plain text with one tab separated record per line:
+//   setup   <game options, one per field>
+//   player  <name>                           (one per seat, in seat order)
+//   move    <player name>  <move>  <unix time>
+
+// Standard Library Import Statements
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package Import Statements
+use tic_tac_toe::game::Game;
+use tic_tac_toe::{move_name, parse_move};
+use crate::setup::{current_seat, new_game, parse_args, seat_count, Options};
+use crate::i18n::{t, tn};
+use crate::messages;
+
+// Header written at the top of the file
+const HEADER: &str = "# tic-tac-toe correspondence v1";
+
+// A move, signed with the name of the player who made it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMove {
+    pub player: String,
+    pub mv: String, // Written the way a player would type it, e.g. "2B"
+    pub time: u64,  // Seconds since the Unix epoch
+}
+
+// Everything needed to replay a correspondence game
+#[derive(Debug, Clone)]
+pub struct Correspondence {
+    setup: Vec<String>, // The command line options the game was set up with
+    names: Vec<String>, // One per seat
+    moves: Vec<SignedMove>,
+}
+
+impl Correspondence {
+    // Constructor for a game nobody has moved in yet
+    pub fn new(options: &Options, names: Vec<String>) -> Self {
+        Self {setup: setup_args(options), names, moves: Vec::new()}
+    }
+
+    // Constructor for a game started at the keyboard, so it can be finished by correspondence.
+    // Each move is signed by whoever sat in the seat that made it.
+    pub fn resume(options: &Options, names: Vec<String>, game: &Game) -> Result<Self, String> {
+        let mut record = Self::new(options, names);
+        for &mv in game.history() {
+            let player = record.names[current_seat(&record.game()?, 0)].clone();
+            record.play(&player, &move_name(game, mv))?;
+        }
+        Ok(record)
+    }
+
+    // Reads a game file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| t!("file.read_failed", path = path.display(), error = err))?;
+        let mut record = Self {setup: Vec::new(), names: Vec::new(), moves: Vec::new()};
+
+        for (number, line) in contents.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || t!("file.invalid_record", path = path.display(), line = number + 1);
+            let fields: Vec<&str> = line.split('\t').collect();
+
+            match fields[..] {
+                ["setup", ref setup @ ..] => record.setup = setup.iter().map(|arg| arg.to_string()).collect(),
+                ["player", name] => record.names.push(name.to_string()),
+                ["move", player, mv, time] => record.moves.push(SignedMove {
+                    player: player.to_string(),
+                    mv: mv.to_string(),
+                    time: time.parse().map_err(|_| invalid())?,
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(record)
+    }
+
+    // Writes the game file atomically: a crash leaves either the old file or the new one, never half of one
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut contents = format!("{}\n", HEADER);
+        contents += &format!("setup{}\n", self.setup.iter().map(|arg| format!("\t{}", arg)).collect::<String>());
+        for name in &self.names {
+            contents += &format!("player\t{}\n", name);
+        }
+        for mv in &self.moves {
+            contents += &format!("move\t{}\t{}\t{}\n", mv.player, mv.mv, mv.time);
+        }
+
+        // Write everything to a temporary file of our own next to the real one, then swap it in
+        let temp_path = sibling(path, &format!(".{}.tmp", process::id()));
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+
+    // Sets up the game and replays every move in it
+    pub fn game(&self) -> Result<Game, String> {
+        let mut game = parse_args(self.setup.iter().cloned()).and_then(|options| new_game(&options))
+            .map_err(|err| t!("correspondence.invalid_setup", error = err))?;
+        if self.names.len() != seat_count(&game) {
+            return Err(tn!("correspondence.players", seat_count(&game)));
+        }
+
+        for (number, mv) in self.moves.iter().enumerate() {
+            let invalid = || t!("correspondence.invalid_move", number = number + 1, move_name = mv.mv);
+            if self.names[current_seat(&game, 0)] != mv.player {
+                return Err(t!("correspondence.out_of_turn", error = invalid(), name = mv.player));
+            }
+            let (row, col, piece) = parse_move(&mv.mv, game.tiles().len(), game.players()).map_err(|_| invalid())?;
+            let result = match piece {
+                Some(piece) => game.make_move_with(row, col, piece),
+                None => game.make_move(row, col),
+            };
+            result.map_err(|_| invalid())?;
+        }
+        Ok(game)
+    }
+
+    // Plays a move for `player`, as long as it is their turn. Returns the game after the move.
+    pub fn play(&mut self, player: &str, input: &str) -> Result<Game, String> {
+        let mut game = self.game()?;
+        if game.is_finished() {
+            return Err(t!("move_error.game_over"));
+        }
+        let to_move = &self.names[current_seat(&game, 0)];
+        if to_move != player {
+            return Err(t!("correspondence.not_your_turn", name = to_move, player = player));
+        }
+
+        let (row, col, piece) = parse_move(input, game.tiles().len(), game.players())
+            .map_err(|err| messages::invalid_move(&err))?;
+        let result = match piece {
+            Some(piece) => game.make_move_with(row, col, piece),
+            None => game.make_move(row, col),
+        };
+        result.map_err(|err| messages::move_error(&err, game.players()))?;
+
+        // Sign the move, writing it the standard way whatever the player typed
+        let mv = *game.history().last().expect("a move was just played");
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+        self.moves.push(SignedMove {player: player.to_string(), mv: move_name(&game, mv), time});
+        Ok(game)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn moves(&self) -> &[SignedMove] {
+        &self.moves
+    }
+}
+
+// Waits until no other player is writing the game, then keeps them out until the returned file is dropped.
+// The lock is on a file next to the game, since saving replaces the game file itself.
+pub fn lock(path: &Path) -> io::Result<File> {
+    let file = OpenOptions::new().create(true).truncate(false).write(true).open(sibling(path, ".lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
+// The options that decide the rules and starting position, written back as command line arguments
+fn setup_args(options: &Options) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut push = |flag: &str, value: String| args.extend(vec![flag.to_string(), value]);
+
+    if let Some(size) = options.size {
+        push("--size", size.to_string());
+    }
+    if let Some(win_length) = options.win_length {
+        push("--win", win_length.to_string());
+    }
+    if let Some(count) = options.player_count {
+        push("--players", count.to_string());
+    }
+    if let Some(symbols) = &options.symbols {
+        push("--symbols", symbols.iter().collect());
+    }
+    if let Some(order) = &options.turn_order {
+        push("--turn-order", order.iter().collect());
+    }
+    if let Some(first) = options.first {
+        push("--first", first.to_string());
+    }
+    for placement in &options.placements {
+        push("--place", placement.clone());
+    }
+    if options.order_chaos {
+        args.push("--order-chaos".to_string());
+    }
+    args
+}
+
+// A file next to `path` with `suffix` added to its name, e.g. game.ttt.lock
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::TryLockError;
+
+    // A game file of its own for each test, in the temp directory
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("tic-tac-toe-correspondence-{}-{}", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn new_record(args: &[&str]) -> Correspondence {
+        let options = parse_args(args.iter().map(|arg| arg.to_string())).unwrap();
+        Correspondence::new(&options, vec!["Ana".to_string(), "Beto".to_string()])
+    }
+
+    #[test]
+    fn moves_are_only_played_in_turn() {
+        let mut record = new_record(&[]);
+        assert!(record.play("Beto", "2B").is_err());
+        assert!(record.play("Nobody", "2B").is_err());
+        record.play("Ana", "b2").unwrap();
+        assert!(record.play("Ana", "1A").is_err());
+        record.play("Beto", "1A").unwrap();
+
+        // Moves are signed by their player and written the standard way
+        let signed: Vec<(&str, &str)> = record.moves().iter().map(|mv| (mv.player.as_str(), mv.mv.as_str())).collect();
+        assert_eq!(signed, [("Ana", "2B"), ("Beto", "1A")]);
+    }
+
+    #[test]
+    fn moves_signed_by_the_wrong_player_are_rejected() {
+        let mut record = new_record(&[]);
+        record.play("Ana", "2B").unwrap();
+        record.play("Beto", "1A").unwrap();
+        assert!(record.game().is_ok());
+
+        // Someone editing the file to sign Beto's move as Ana's makes the game unreadable
+        record.moves[1].player = "Ana".to_string();
+        assert!(record.game().is_err());
+    }
+
+    #[test]
+    fn games_survive_a_round_trip() {
+        let path = temp_path("round-trip.ttt");
+        let mut record = new_record(&["--size", "4", "--win", "3", "--place", "4D:o"]);
+        record.play("Ana", "1A").unwrap();
+        record.play("Beto", "2B").unwrap();
+        record.save(&path).unwrap();
+
+        let loaded = Correspondence::load(&path).unwrap();
+        assert_eq!(loaded.names(), record.names());
+        assert_eq!(loaded.moves(), record.moves());
+        assert_eq!(loaded.game().unwrap().to_board_string(), record.game().unwrap().to_board_string());
+        assert!(!sibling(&path, &format!(".{}.tmp", process::id())).exists());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn the_lock_keeps_other_players_out() {
+        let path = temp_path("locked.ttt");
+        let lock_path = sibling(&path, ".lock");
+        let held = lock(&path).unwrap();
+
+        // Another handle on the same lock file has to wait while the lock is held
+        let other = OpenOptions::new().write(true).open(&lock_path).unwrap();
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+        drop(held);
+        other.try_lock().unwrap();
+        fs::remove_file(&lock_path).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+// Everything that can stop the program, reported by main before it exits
+
+// Standard Library Import Statements
+use std::error;
+use std::fmt;
+use std::io;
+
+// Package Import Statements
+use tic_tac_toe::{InvalidMove, MoveError};
+use crate::i18n::t;
+use crate::messages;
+
+// An error that ends the program
+pub enum Error {
+    Io(io::Error),       // Reading input or writing a file failed
+    Parse(InvalidMove),  // A move that isn't written like one, e.g. "B2x"
+    Move(MoveError),     // A move the rules don't allow
+    Message(String),     // Bad command line arguments, setups and anything else already explained in words
+    EndOfInput,          // Stdin closed while a prompt was waiting, e.g. Ctrl-D or a piped file ran out
+    Interrupted,         // The player pressed Ctrl-C
+}
+
+impl Error {
+    // Status the program exits with: 1 for errors, 3 when the input ran out and 130 after Ctrl-C
+    // (the code a shell reports for a program killed by SIGINT)
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::EndOfInput => 3,
+            Error::Interrupted => 130,
+            _ => 1,
+        }
+    }
+
+    // Whether the player stopped the program, rather than something going wrong
+    pub fn is_quit(&self) -> bool {
+        matches!(self, Error::EndOfInput | Error::Interrupted)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse(err) => write!(f, "{}", messages::invalid_move(err)),
+            Error::Move(err) => write!(f, "{}", messages::move_error(err, None)),
+            Error::Message(message) => write!(f, "{}", message),
+            Error::EndOfInput => write!(f, "{}", t!("quit.end_of_input")),
+            Error::Interrupted => write!(f, "{}", t!("quit.interrupted")),
+        }
+    }
+}
+
+// Debug shows the message as well, the player should never see the variant names
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse(err) => Some(err),
+            Error::Move(err) => Some(err),
+            Error::Message(_) | Error::EndOfInput | Error::Interrupted => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<InvalidMove> for Error {
+    fn from(err: InvalidMove) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<MoveError> for Error {
+    fn from(err: MoveError) -> Self {
+        Error::Move(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Message(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Message(message.to_string())
+    }
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tic_tac_toe::{parse_move, Piece, Players};
+
+    #[test]
+    fn errors_read_like_the_messages_players_see() {
+        let invalid = parse_move("9Z", 3, &Players::new(2).unwrap()).unwrap_err();
+        assert_eq!(Error::from(invalid.clone()).to_string(), messages::invalid_move(&invalid));
+
+        let err = MoveError::TileNotEmpty {other_piece: Piece::X, row: 1, col: 1};
+        assert_eq!(Error::from(err.clone()).to_string(), messages::move_error(&err, None));
+        assert_eq!(format!("{:?}", Error::from(err.clone())), messages::move_error(&err, None));
+        assert_eq!(Error::from("No such file").to_string(), "No such file");
+        assert_eq!(Error::EndOfInput.to_string(), t!("quit.end_of_input"));
+    }
+
+    #[test]
+    fn the_cause_is_kept() {
+        let err = MoveError::GameAlreadyOver;
+        let wrapped = Error::from(err.clone());
+        assert_eq!(error::Error::source(&wrapped).unwrap().to_string(), err.to_string());
+        assert!(error::Error::source(&Error::Interrupted).is_none());
+    }
+
+    #[test]
+    fn quitting_exits_with_its_own_status() {
+        let io = Error::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        assert_eq!((io.exit_code(), io.is_quit()), (1, false));
+        assert_eq!((Error::from(MoveError::PieceRequired).exit_code(), Error::from("bad").exit_code()), (1, 1));
+        assert_eq!((Error::EndOfInput.exit_code(), Error::EndOfInput.is_quit()), (3, true));
+        assert_eq!((Error::Interrupted.exit_code(), Error::Interrupted.is_quit()), (130, true));
+    }
+}
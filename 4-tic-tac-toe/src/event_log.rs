@@ -0,0 +1,116 @@
+// Logs everything that happens in a game to a file, one JSON object per line, e.g.
+//   {"time":1700000000123,"event":"MovePlayed","tile":"2B","row":1,"col":1,"piece":"x"}
+//   {"time":1700000004567,"event":"MoveRejected","error":{"kind":"TileNotEmpty",...}}
+//   {"time":1700000009012,"event":"GameOver","winner":"x"}
+// Times are milliseconds since the Unix epoch. Rows and columns count from 0.
+
+// Standard Library Import Statements
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, GameEvent, Move, Players};
+use tic_tac_toe::tile_name;
+use serde_json::Value;
+use crate::i18n::t;
+use crate::machine;
+
+// Starts logging a game's events to the end of a file, creating it if needed
+pub fn attach(game: &mut Game, path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let players = game.players().clone();
+    let path = path.to_path_buf();
+
+    game.subscribe(move |event| {
+        // A full disk shouldn't end the game, but the player should know the log has a gap
+        if let Err(err) = writeln!(file, "{}", log_line(&players, event)) {
+            eprintln!("{}", t!("log.write_failed", path = path.display(), error = err));
+        }
+    });
+    Ok(())
+}
+
+// Describes an event as a line of the log
+fn log_line(players: &Players, event: &GameEvent) -> Value {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis() as usize);
+    let mut fields = vec![("time", Value::from(time))];
+
+    match event {
+        GameEvent::MovePlayed(mv) => {
+            fields.push(("event", "MovePlayed".into()));
+            fields.extend(move_fields(players, *mv));
+        },
+        GameEvent::MoveRejected(err) => {
+            fields.push(("event", "MoveRejected".into()));
+            fields.push(("error", machine::move_error(players, err)));
+        },
+        GameEvent::GameOver(winner) => {
+            fields.push(("event", "GameOver".into()));
+            fields.push(("winner", machine::winner_name(players, *winner).into()));
+        },
+        GameEvent::Undo(mv) => {
+            fields.push(("event", "Undo".into()));
+            fields.extend(move_fields(players, *mv));
+        },
+        GameEvent::TimedOut(piece) => {
+            fields.push(("event", "TimedOut".into()));
+            fields.push(("piece", players.symbol(*piece).to_string().into()));
+        },
+        // Events added to the library later are logged by name
+        other => fields.push(("event", format!("{:?}", other).into())),
+    }
+    Value::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+// Where a move was played and which piece it placed
+fn move_fields(players: &Players, mv: Move) -> Vec<(&'static str, Value)> {
+    vec![
+        ("tile", tile_name(mv.row, mv.col).into()),
+        ("row", mv.row.into()),
+        ("col", mv.col.into()),
+        ("piece", players.symbol(mv.piece).to_string().into()),
+    ]
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn log_lines_read_back_as_the_events() {
+        let path = env::temp_dir().join(format!("tic-tac-toe-events-{}.jsonl", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut game = Game::new();
+        attach(&mut game, &path).unwrap();
+
+        game.make_move(1, 1).unwrap();
+        game.make_move(1, 1).unwrap_err();
+        game.undo().unwrap();
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+        drop(game);
+
+        let lines: Vec<Value> = fs::read_to_string(&path).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let field = |line: &Value, key: &str| line.get(key).and_then(Value::as_str).map(String::from);
+        let events: Vec<String> = lines.iter().map(|line| field(line, "event").unwrap()).collect();
+        assert_eq!(events, [
+            "MovePlayed", "MoveRejected", "Undo", "MovePlayed", "MovePlayed", "MovePlayed", "MovePlayed", "MovePlayed", "GameOver",
+        ]);
+
+        // Each line has what is needed to replay the event
+        assert_eq!((field(&lines[0], "tile"), field(&lines[0], "piece")), (Some("2B".into()), Some("x".into())));
+        assert_eq!(lines[0].get("row").map(ToString::to_string), Some("1".to_string()));
+        assert_eq!(lines[1].get("error").and_then(|error| field(error, "kind")), Some("TileNotEmpty".into()));
+        assert_eq!(field(&lines[2], "tile"), Some("2B".into()));
+        assert_eq!(field(&lines[8], "winner"), Some("x".into()));
+        assert!(lines.iter().all(|line| line.get("time").is_some()));
+        fs::remove_file(&path).unwrap();
+    }
+}
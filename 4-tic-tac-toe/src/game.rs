@@ -1,68 +1,416 @@
 // This is a excerpt of the excellent tutorial by sunjay at https://github.com/sunjay/tic-tac-toe
 // Please checkout his tutorial for a more in depth explanation of code.
 
-// Global variable
+// Standard Library Import Statements
+use std::error;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+
+// Package Import Statements
+use crate::notation::tile_name;
+
+// Global variables
 const BOARD_SIZE: usize = 3;
+const ORDER_AND_CHAOS_SIZE: usize = 6;
+const ORDER_AND_CHAOS_WIN_LENGTH: usize = 5;
 
-// Define the pieces
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] // Derive useful traits automatically
-pub enum Piece {
-    X,
-    O,
-}
+/// Symbol of an empty tile in board string notation, e.g. "xo./.x./... o"
+pub const EMPTY_SYMBOL: char = '.';
+const ROW_SEPARATOR: char = '/';
+
+// Horizontal, vertical, left diagonal (\) and right diagonal (/)
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+// Symbols handed out to players that don't pick their own
+const DEFAULT_SYMBOLS: [char; 8] = ['x', 'o', '+', '*', '#', '@', '%', '&'];
+
+/// Define the pieces. Each player owns one piece, numbered from 0 in the order they were given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)] // Derive useful traits automatically
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Piece(u8);
 
-// Implement Piece and give it the "other" function
+// Implement Piece and give names to the two classic pieces
 impl Piece {
-    // Helper function to determine the other piece
-    pub fn other(self) -> Piece {
-        match self {
-            Piece::X => Piece::O,
-            Piece::O => Piece::X,
+    pub const X: Piece = Piece(0);
+    pub const O: Piece = Piece(1);
+
+    /// Constructor for the piece of the player at `index`
+    pub fn new(index: usize) -> Piece {
+        Piece(index as u8)
+    }
+
+    /// Position of this piece's player in the player list
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+// Impl Display to show the piece with its default symbol (x, o, +, ...)
+// Games where players pick their own symbols should use Players::symbol instead
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match DEFAULT_SYMBOLS.get(self.index()) {
+            Some(symbol) => write!(f, "{}", symbol),
+            None => write!(f, "#{}", self.index() + 1),
         }
     }
 }
 
-// Type aliases
+/// Describes who is playing: one symbol per piece and the order they take turns in
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serde_support::PlayersRecord", try_from = "serde_support::PlayersRecord"))]
+pub struct Players {
+    symbols: Vec<char>,
+    turn_order: Vec<Piece>,
+}
+
+impl Players {
+    /// Constructor for `count` players using the default symbols (x, o, +, ...)
+    pub fn new(count: usize) -> Result<Self, SetupError> {
+        if count > DEFAULT_SYMBOLS.len() {
+            return Err(SetupError::TooManyPlayers {count});
+        }
+        Self::with_symbols(DEFAULT_SYMBOLS[..count].to_vec())
+    }
+
+    /// Constructor for one player per symbol, taking turns in the order given
+    pub fn with_symbols(symbols: Vec<char>) -> Result<Self, SetupError> {
+        // At least two players are needed to take turns
+        if symbols.len() < 2 {
+            return Err(SetupError::TooFewPlayers {count: symbols.len()});
+        }
+        else if symbols.len() > u8::MAX as usize {
+            return Err(SetupError::TooManyPlayers {count: symbols.len()});
+        }
+
+        // Every player needs a symbol the others can tell apart
+        for (i, &symbol) in symbols.iter().enumerate() {
+            if symbol == EMPTY_SYMBOL || symbol == ROW_SEPARATOR || symbol.is_whitespace() {
+                return Err(SetupError::ReservedSymbol {symbol});
+            }
+            else if symbols[..i].contains(&symbol) {
+                return Err(SetupError::DuplicateSymbol {symbol});
+            }
+        }
+
+        let turn_order = (0..symbols.len()).map(Piece::new).collect();
+        Ok(Self {symbols, turn_order})
+    }
+
+    /// Replaces the turn order. Every piece must appear exactly once.
+    pub fn with_turn_order(mut self, turn_order: Vec<Piece>) -> Result<Self, SetupError> {
+        let mut sorted = turn_order.clone();
+        sorted.sort();
+        if !sorted.iter().copied().eq((0..self.count()).map(Piece::new)) {
+            return Err(SetupError::InvalidTurnOrder);
+        }
+
+        self.turn_order = turn_order;
+        Ok(self)
+    }
+
+    /// Finds the piece drawn with a given symbol
+    pub fn piece_for(&self, symbol: char) -> Option<Piece> {
+        self.symbols.iter().position(|&s| s == symbol).map(Piece::new)
+    }
+
+    /// Character used to draw a piece
+    pub fn symbol(&self, piece: Piece) -> char {
+        self.symbols[piece.index()]
+    }
+
+    /// Number of players
+    pub fn count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Pieces in the order their players take turns
+    pub fn turn_order(&self) -> &[Piece] {
+        &self.turn_order
+    }
+}
+
+/// A tile on the board, holding a piece or nothing
 pub type Tile = Option<Piece>;
-pub type Tiles = [[Tile; BOARD_SIZE]; BOARD_SIZE];
+/// The board, indexed by row then column from the top left
+pub type Tiles = Vec<Vec<Tile>>;
+
+/// A piece placed on a tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+    pub piece: Piece,
+}
+
+/// Define the rule sets that can be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    Standard,      // Classic 3x3, X and O take turns placing their own piece
+    OrderAndChaos, // 6x6, both players may place either piece
+}
+
+/// Define the two sides of an Order and Chaos game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Role {
+    Order, // Wants five in a row of either piece
+    Chaos, // Wants to fill the board without five in a row
+}
 
-// Define Game Over states
+/// Define Game Over states: who won, or a tie
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Winner {
-    X,
-    O,
+    Player(Piece), // The player owning this piece got a line
+    Order,
+    Chaos,
     Tie,
 }
 
-// Define errors in intended moves
-#[derive(Debug, Clone)]
+/// Define errors in intended moves
+/// More kinds of error may be added, so matches on it need a wildcard arm outside this crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum MoveError {
     GameAlreadyOver, // Should not occur in normal operation
     InvalidPosition { row: usize, col: usize }, // Invalid tile DNE
     TileNotEmpty { other_piece: Piece, row: usize, col: usize }, // Valid tile, but occupied
+    WrongPiece { piece: Piece }, // Standard games only allow the current piece
+    PieceRequired, // Order and Chaos moves must say which piece is placed
+}
+
+// Impl Display to explain the error to a player, naming tiles the way they are typed (e.g. 2B)
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.with_symbols(None))
+    }
+}
+
+impl error::Error for MoveError {}
+
+impl MoveError {
+    /// Displays the error with the symbols the players actually use, rather than the default ones
+    pub fn with_symbols<'a, P: Into<Option<&'a Players>>>(&'a self, players: P) -> impl fmt::Display + 'a {
+        MoveErrorDisplay {err: self, players: players.into()}
+    }
+}
+
+// Helper for MoveError::with_symbols
+struct MoveErrorDisplay<'a> {
+    err: &'a MoveError,
+    players: Option<&'a Players>,
+}
+
+impl fmt::Display for MoveErrorDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = |piece: Piece| match self.players {
+            Some(players) if piece.index() < players.count() => players.symbol(piece).to_string(),
+            _ => piece.to_string(),
+        };
+
+        match *self.err {
+            MoveError::GameAlreadyOver => write!(f, "The game is already over!"),
+            MoveError::InvalidPosition {row, col} => write!(f, "There is no tile {} on the board!", tile_name(row, col)),
+            MoveError::TileNotEmpty {other_piece, row, col} => {
+                write!(f, "The tile at position {} already has piece {} in it!", tile_name(row, col), symbol(other_piece))
+            },
+            MoveError::WrongPiece {piece} => write!(f, "It is not {}'s turn!", symbol(piece)),
+            MoveError::PieceRequired => write!(f, "Please choose a piece to place (e.g. 3D o)."),
+        }
+    }
+}
+
+/// Define errors in setting up a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SetupError {
+    TooFewPlayers { count: usize },
+    TooManyPlayers { count: usize },
+    DuplicateSymbol { symbol: char },
+    InvalidTurnOrder, // The turn order must list every piece exactly once
+    InvalidBoardSize { size: usize }, // Boards are 1 to 26 tiles wide (columns are lettered A-Z)
+    InvalidWinLength { win_length: usize, size: usize }, // A line has to fit on the board
+    UnsupportedPlayers { variant: Variant }, // Order and Chaos is always played by two
+    UnsupportedStartingPiece { variant: Variant }, // Order always moves first in Order and Chaos
+    UnknownPiece { piece: Piece }, // The piece doesn't belong to any player
+    InvalidPosition { row: usize, col: usize }, // Placed piece is off the board
+    TilePlacedTwice { row: usize, col: usize }, // Two pieces were placed on one tile
+    MultipleWinners, // More than one player already has a line
+    ReservedSymbol { symbol: char }, // '.', '/' and spaces have a meaning in board strings
+    UnknownSymbol { symbol: char }, // A board string used a symbol no player has
+    InvalidBoardString, // Rows have to be the same length as there are rows
+}
+
+// Impl Display to explain the problem to whoever set the game up, naming tiles the way they are typed
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SetupError::TooFewPlayers {count} => write!(f, "A game needs at least 2 players, not {}", count),
+            SetupError::TooManyPlayers {count} => write!(f, "There are too many players ({})", count),
+            SetupError::DuplicateSymbol {symbol} => write!(f, "Two players use the symbol '{}'", symbol),
+            SetupError::InvalidTurnOrder => write!(f, "The turn order must list every player exactly once"),
+            SetupError::InvalidBoardSize {size} => write!(f, "The board must be 1 to 26 tiles wide, not {}", size),
+            SetupError::InvalidWinLength {win_length, size} => {
+                write!(f, "A line to win must be 1 to {} long on this board, not {}", size, win_length)
+            },
+            SetupError::UnsupportedPlayers {variant} => write!(f, "{} is always played by two players", variant_name(variant)),
+            SetupError::UnsupportedStartingPiece {variant} => {
+                write!(f, "The first player can't be chosen in {}, the turn order is fixed", variant_name(variant))
+            },
+            SetupError::UnknownPiece {piece} => write!(f, "No player has the piece {}", piece),
+            SetupError::InvalidPosition {row, col} => write!(f, "There is no tile {} on the board", tile_name(row, col)),
+            SetupError::TilePlacedTwice {row, col} => write!(f, "Two pieces were placed on {}", tile_name(row, col)),
+            SetupError::MultipleWinners => write!(f, "More than one player already has a line"),
+            SetupError::ReservedSymbol {symbol} => write!(f, "{:?} can't be a player's symbol, it has a meaning in board strings", symbol),
+            SetupError::UnknownSymbol {symbol} => write!(f, "No player uses the symbol '{}'", symbol),
+            SetupError::InvalidBoardString => {
+                write!(f, "Invalid board string: rows are separated by '/' and each has as many tiles as there are rows")
+            },
+        }
+    }
 }
 
-// Define Game State
+impl error::Error for SetupError {}
+
+// Names a variant in error messages
+fn variant_name(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Standard => "Standard tic-tac-toe",
+        Variant::OrderAndChaos => "Order and Chaos",
+    }
+}
+
+/// Define the events a game reports to its observers
 #[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum GameEvent {
+    MovePlayed(Move),
+    MoveRejected(MoveError),
+    GameOver(Winner), // Sent after the move that ended the game, or when a player runs out of time
+    Undo(Move),       // The move that was taken back
+    TimedOut(Piece),  // The player of this piece ran out of time and is skipped from now on (standard games only)
+}
+
+/// A callback that is told about everything that happens in a game
+pub type Observer = Box<dyn FnMut(&GameEvent) + Send>;
+
+// The observers of a game. Copies of a game (like the ones the solver searches with) start without any.
+#[derive(Default)]
+struct Observers(Vec<Observer>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+/// Define Game State: the rules, the board, whose turn it is and the moves played so far
+/// With the `serde` feature, deserializing checks the game the same way GameBuilder does and works out the
+/// winner from the board and the players who ran out of time.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serde_support::GameRecord", try_from = "serde_support::GameRecord"))]
 pub struct Game {
+    variant: Variant,
     tiles: Tiles,
-    current_piece: Piece,
+    win_length: usize,
+    players: Players,
+    turn: usize, // Index into the turn order
+    moves_made: usize,
     winner: Option<Winner>,
+    history: Vec<Move>, // Moves played so far, oldest first (not counting pieces placed by the builder)
+    timed_out: Vec<Piece>, // Players who ran out of time, in the order they did. Their turns are skipped.
+    observers: Observers,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Implementation of a Game struct
 impl Game {
-    // Constructor
+    /// Constructor for a standard 3x3 game between x and o
     pub fn new() -> Self {
-        Self {
-            tiles: Default::default(),
-            current_piece: Piece::X,
-            winner: None,
+        Self::with_variant(Variant::Standard)
+    }
+
+    /// Constructor for a given rule set
+    pub fn with_variant(variant: Variant) -> Self {
+        let (size, win_length) = match variant {
+            Variant::Standard => (BOARD_SIZE, BOARD_SIZE),
+            Variant::OrderAndChaos => (ORDER_AND_CHAOS_SIZE, ORDER_AND_CHAOS_WIN_LENGTH),
+        };
+
+        let players = Players::new(2).expect("two players are always valid");
+        Self::with_rules(variant, size, win_length, players).expect("built-in variants are always valid")
+    }
+
+    /// Constructor for a custom board, line length and set of players
+    pub fn with_rules(variant: Variant, size: usize, win_length: usize, players: Players) -> Result<Self, SetupError> {
+        // Check the board is one we can label and a line can fit on it
+        if size == 0 || size > 26 {
+            return Err(SetupError::InvalidBoardSize {size});
+        }
+        else if win_length == 0 || win_length > size {
+            return Err(SetupError::InvalidWinLength {win_length, size});
         }
+        else if variant == Variant::OrderAndChaos && players.count() != 2 {
+            return Err(SetupError::UnsupportedPlayers {variant});
+        }
+
+        Ok(Self {
+            variant,
+            tiles: vec![vec![None; size]; size],
+            win_length,
+            players,
+            turn: 0,
+            moves_made: 0,
+            winner: None,
+            history: Vec::new(),
+            timed_out: Vec::new(),
+            observers: Observers::default(),
+        })
     }
 
-    // Modifies the gameboard by placing the current piece at a given (row, col)
+    /// Modifies the gameboard by placing the current piece at a given (row, col)
     pub fn make_move(&mut self, row: usize, col: usize) -> Result<(), MoveError> {
+        // In Order and Chaos there is no "current piece", the player has to pick one
+        if self.variant == Variant::OrderAndChaos && !self.is_finished() {
+            return self.reject(MoveError::PieceRequired);
+        }
+
+        self.make_move_with(row, col, self.current_piece())
+    }
+
+    /// Modifies the gameboard by placing the chosen piece at a given (row, col)
+    pub fn make_move_with(&mut self, row: usize, col: usize, piece: Piece) -> Result<(), MoveError> {
+        if let Err(err) = self.place_piece(row, col, piece) {
+            return self.reject(err);
+        }
+
+        // Tell everyone watching about the move, and the result if it ended the game
+        self.notify(&GameEvent::MovePlayed(Move {row, col, piece}));
+        if let Some(winner) = self.winner {
+            self.notify(&GameEvent::GameOver(winner));
+        }
+        Ok(())
+    }
+
+    // Checks a move and updates the board if it is allowed
+    fn place_piece(&mut self, row: usize, col: usize, piece: Piece) -> Result<(), MoveError> {
         // Check if Game is over
         if self.is_finished() {
             return Err(MoveError::GameAlreadyOver);
@@ -75,72 +423,166 @@ impl Game {
         else if let Some(other_piece) = self.tiles[row][col] {
             return Err(MoveError::TileNotEmpty {other_piece, row, col});
         }
+        // Check that the player is allowed to place this piece
+        else if piece.index() >= self.players.count()
+            || (self.variant == Variant::Standard && piece != self.current_piece())
+        {
+            return Err(MoveError::WrongPiece {piece});
+        }
 
-        // Update state
-        self.tiles[row][col] = Some(self.current_piece);
-        self.current_piece = self.current_piece.other();
+        // Update state, rotating to the next player in the turn order
+        self.tiles[row][col] = Some(piece);
+        self.moves_made += 1;
+        self.turn = self.next_turn(self.turn);
+        self.history.push(Move {row, col, piece});
         self.update_winner(row, col);
 
         // If everything worked, we'll return Ok
         Ok(())
     }
 
-    // Checks for any winning states and updates the winner accordingly
-    fn update_winner(&mut self, row: usize, col: usize) {
-        // Used for sanity check
-        let rows = self.tiles.len();
-        let cols = self.tiles[0].len();
+    /// Takes the player to move out of the game because they ran out of time.
+    ///
+    /// With two sides the other side wins. With three or more players the flagged player loses their place:
+    /// their turns are skipped from now on and the game goes on between the others, until only one of
+    /// them is left in time and wins. Returns GameAlreadyOver if the game had already ended.
+    pub fn time_out(&mut self) -> Result<(), MoveError> {
+        if self.is_finished() {
+            return Err(MoveError::GameAlreadyOver);
+        }
 
-        // Gets the tiles in a given row and col
-        let tiles_row = self.tiles[row];
-        let tiles_col = [self.tiles[0][col], self.tiles[1][col], self.tiles[2][col]];
+        let flagged = self.current_piece();
+        self.timed_out.push(flagged);
+        if self.variant == Variant::Standard {
+            self.turn = self.next_turn(self.turn);
+            self.notify(&GameEvent::TimedOut(flagged));
+        }
 
-        // Break if there are too many rows or cols
-        assert!(rows == 3 && cols == 3,
-            "This code is written with the assumption that there are three rows and columns");
+        self.winner = self.time_out_winner();
+        if let Some(winner) = self.winner {
+            self.notify(&GameEvent::GameOver(winner));
+        }
+        Ok(())
+    }
 
-        // Check left diagonal (\)
-        let tiles_diagonal_1 = if row == col {
-            [self.tiles[0][0], self.tiles[1][1], self.tiles[2][2]]
+    // Who won on time: the other role in Order and Chaos, or the last player left in time
+    fn time_out_winner(&self) -> Option<Winner> {
+        let first = *self.timed_out.first()?;
+        let mut in_time = self.players.turn_order().iter().filter(|piece| !self.timed_out.contains(piece));
+        match self.variant {
+            Variant::OrderAndChaos if first == self.players.turn_order()[0] => Some(Winner::Chaos),
+            Variant::OrderAndChaos => Some(Winner::Order),
+            Variant::Standard => match (in_time.next(), in_time.next()) {
+                (Some(&last), None) => Some(Winner::Player(last)),
+                _ => None,
+            },
         }
-        else {
-            [None, None, None]
-        };
+    }
+
+    // The turn after `turn`, skipping the players who ran out of time
+    fn next_turn(&self, turn: usize) -> usize {
+        let turn_order = self.players.turn_order();
+        let mut next = (turn + 1) % turn_order.len();
+        while self.timed_out.contains(&turn_order[next]) && next != turn {
+            next = (next + 1) % turn_order.len();
+        }
+        next
+    }
 
-        // Check right diagonal (/)
-        let tiles_diagonal_2 = if (rows - row - 1) == col {
-            [self.tiles[0][2], self.tiles[1][1], self.tiles[2][0]]
+    /// Takes back the last move played, returning it, or None if there is nothing to take back.
+    /// Once anyone has run out of time nothing can be taken back, since that would give their time back.
+    /// Otherwise the game can only have ended with the last move, so taking it back reopens the game.
+    pub fn undo(&mut self) -> Option<Move> {
+        if !self.timed_out.is_empty() {
+            return None;
         }
-        else {
-            [None, None, None]
+        let mv = self.history.pop()?;
+        self.tiles[mv.row][mv.col] = None;
+        self.moves_made -= 1;
+        self.turn = (self.turn + self.players.turn_order().len() - 1) % self.players.turn_order().len();
+        self.winner = None;
+        self.notify(&GameEvent::Undo(mv));
+        Some(mv)
+    }
+
+    /// The players who ran out of time, in the order they did
+    pub fn timed_out(&self) -> &[Piece] {
+        &self.timed_out
+    }
+
+    /// Calls `observer` with every event from now on
+    pub fn subscribe<F: FnMut(&GameEvent) + Send + 'static>(&mut self, observer: F) {
+        self.observers.0.push(Box::new(observer));
+    }
+
+    /// Sends every event from now on down a channel, for observers on another thread
+    pub fn events(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribe(move |event| {
+            // Nobody listening any more is fine
+            let _ = sender.send(event.clone());
+        });
+        receiver
+    }
+
+    // Tells every observer about an event
+    fn notify(&mut self, event: &GameEvent) {
+        for observer in &mut self.observers.0 {
+            observer(event);
+        }
+    }
+
+    // Reports a rejected move to the observers and hands the error back
+    fn reject(&mut self, err: MoveError) -> Result<(), MoveError> {
+        self.notify(&GameEvent::MoveRejected(err.clone()));
+        Err(err)
+    }
+
+    /// Plays a move, same as make_move_with
+    pub fn play(&mut self, mv: Move) -> Result<(), MoveError> {
+        self.make_move_with(mv.row, mv.col, mv.piece)
+    }
+
+    /// Lists every move the current player is allowed to make
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+
+        // Order and Chaos players may place either piece, everyone else only their own
+        let pieces = match self.variant {
+            Variant::Standard => vec![self.current_piece()],
+            Variant::OrderAndChaos => vec![Piece::X, Piece::O],
         };
 
-        // Check for game overs and set the winner variable
-        fn check_winner(row: &[Tile]) -> Option<Winner> {
-            if row[0] == row[1] && row[1] == row[2] {
-                match row[0] {
-                    Some(Piece::X) => Some(Winner::X),
-                    Some(Piece::O) => Some(Winner::O),
-                    None => None,
+        let mut moves = Vec::new();
+        for (row, tiles_row) in self.tiles.iter().enumerate() {
+            for (col, tile) in tiles_row.iter().enumerate() {
+                if tile.is_none() {
+                    moves.extend(pieces.iter().map(|&piece| Move {row, col, piece}));
                 }
             }
-
-            else {
-                None
-            }
         }
+        moves
+    }
 
-        // Set winner for any of the win states
-        self.winner = self.winner
-            .or_else(|| check_winner(&tiles_row))
-            .or_else(|| check_winner(&tiles_col))
-            .or_else(|| check_winner(&tiles_diagonal_1))
-            .or_else(|| check_winner(&tiles_diagonal_2));
+    // Checks for any winning states and updates the winner accordingly
+    fn update_winner(&mut self, row: usize, col: usize) {
+        // Check the four lines that run through the new piece
+        if self.has_line_through(row, col) {
+            self.winner = match self.variant {
+                Variant::OrderAndChaos => Some(Winner::Order),
+                Variant::Standard => self.tiles[row][col].map(Winner::Player),
+            };
+        }
 
-        // In the absence of a winner and a valid move, Set winner to Tie
+        // In the absence of a winner and a valid move, the game is over
         self.winner = self.winner.or_else(|| {
             if self.tiles.iter().all(|row| row.iter().all(|tile| tile.is_some())) {
-                Some(Winner::Tie)
+                match self.variant {
+                    Variant::Standard => Some(Winner::Tie),
+                    Variant::OrderAndChaos => Some(Winner::Chaos), // Chaos wins by filling the board
+                }
             }
             // If all checks fail, winner is None
             else {
@@ -149,22 +591,787 @@ impl Game {
         });
     }
 
+    // Checks if the piece at (row, col) is part of `win_length` matching pieces in a row
+    fn has_line_through(&self, row: usize, col: usize) -> bool {
+        let piece = match self.tiles[row][col] {
+            Some(piece) => piece,
+            None => return false,
+        };
+
+        DIRECTIONS.iter().any(|&(d_row, d_col)| {
+            // Count the piece itself, then walk away from it in both directions
+            let count = 1
+                + self.count_matching(row, col, d_row, d_col, piece)
+                + self.count_matching(row, col, -d_row, -d_col, piece);
+            count >= self.win_length
+        })
+    }
+
+    // Counts matching pieces starting next to (row, col) and walking in one direction
+    fn count_matching(&self, row: usize, col: usize, d_row: isize, d_col: isize, piece: Piece) -> usize {
+        let size = self.tiles.len() as isize;
+        let mut count = 0;
+        let (mut r, mut c) = (row as isize + d_row, col as isize + d_col);
+
+        while r >= 0 && r < size && c >= 0 && c < size && self.tiles[r as usize][c as usize] == Some(piece) {
+            count += 1;
+            r += d_row;
+            c += d_col;
+        }
+
+        count
+    }
+
+    // Checks the whole board for a winner, rather than just the lines through the last move
+    fn scan_winner(&self) -> Result<Option<Winner>, SetupError> {
+        let size = self.tiles.len();
+        let mut winner = None;
+
+        for row in 0..size {
+            for col in 0..size {
+                if !self.has_line_through(row, col) {
+                    continue;
+                }
+
+                let found = match self.variant {
+                    Variant::OrderAndChaos => Winner::Order,
+                    Variant::Standard => Winner::Player(self.tiles[row][col].expect("lines are made of pieces")),
+                };
+                // Two different players can't both have won
+                if winner.is_some() && winner != Some(found) {
+                    return Err(SetupError::MultipleWinners);
+                }
+                winner = Some(found);
+            }
+        }
+
+        // A full board without a line ends the game too
+        let full = self.tiles.iter().all(|row| row.iter().all(|tile| tile.is_some()));
+        Ok(winner.or(match (full, self.variant) {
+            (false, _) => None,
+            (true, Variant::Standard) => Some(Winner::Tie),
+            (true, Variant::OrderAndChaos) => Some(Winner::Chaos),
+        }))
+    }
+
+    /// Finds a line of `win_length` or more matching pieces, returning the (row, col) of the tiles at either end
+    pub fn winning_line(&self) -> Option<((usize, usize), (usize, usize))> {
+        let size = self.tiles.len();
+
+        for row in 0..size {
+            for col in 0..size {
+                let piece = match self.tiles[row][col] {
+                    Some(piece) => piece,
+                    None => continue,
+                };
+
+                // Count each line once, from its first piece
+                for &(d_row, d_col) in &DIRECTIONS {
+                    if self.count_matching(row, col, -d_row, -d_col, piece) > 0 {
+                        continue;
+                    }
+                    let count = self.count_matching(row, col, d_row, d_col, piece);
+                    if count + 1 >= self.win_length {
+                        let end_row = (row as isize + d_row * count as isize) as usize;
+                        let end_col = (col as isize + d_col * count as isize) as usize;
+                        return Some(((row, col), (end_row, end_col)));
+                    }
+                }
+            }
+        }
+        None
+    }
+
     // ACCESSOR FUNCTIONS //
+    /// Checks if the game has been won or tied
     pub fn is_finished(&self) -> bool {
         self.winner.is_some()
     }
 
+    /// How the game ended, or None while it is still going
     pub fn winner(&self) -> Option<Winner> {
         self.winner
     }
 
+    /// The piece placed by the player to move (in Order and Chaos, the piece of the player to move first)
     pub fn current_piece(&self) -> Piece {
-        self.current_piece
+        self.players.turn_order()[self.turn]
+    }
+
+    /// Which side moves next in Order and Chaos (Order always goes first)
+    pub fn current_role(&self) -> Option<Role> {
+        match self.variant {
+            Variant::Standard => None,
+            Variant::OrderAndChaos if self.moves_made.is_multiple_of(2) => Some(Role::Order),
+            Variant::OrderAndChaos => Some(Role::Chaos),
+        }
+    }
+
+    /// Pieces in a row needed to win
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    pub fn players(&self) -> &Players {
+        &self.players
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
     }
 
     pub fn tiles(&self) -> &Tiles {
         &self.tiles
     }
+
+    /// Moves played so far, oldest first (not counting pieces placed by the builder)
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Writes the board in board string notation: rows from top to bottom separated by '/',
+    /// one symbol per tile with '.' for empty, then a space and the symbol of the player to move
+    /// (left out for Order and Chaos, where Order always moves first). e.g. `xo./.x./... o`
+    pub fn to_board_string(&self) -> String {
+        let rows: Vec<String> = self.tiles.iter()
+            .map(|row| row.iter().map(|tile| tile.map_or(EMPTY_SYMBOL, |piece| self.players.symbol(piece))).collect())
+            .collect();
+        let board = rows.join(&ROW_SEPARATOR.to_string());
+
+        match self.variant {
+            Variant::Standard => format!("{} {}", board, self.players.symbol(self.current_piece())),
+            Variant::OrderAndChaos => board,
+        }
+    }
+}
+
+/// Sets up a game that doesn't start from the usual empty board
+/// e.g. `GameBuilder::new().starting_piece(Piece::O).place(1, 1, Piece::X).build()`
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    variant: Variant,
+    size: Option<usize>,
+    win_length: Option<usize>,
+    players: Option<Players>,
+    starting_piece: Option<Piece>,
+    placements: Vec<(usize, usize, Piece)>,
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameBuilder {
+    /// Constructor for a standard game, settings left alone keep their usual values
+    pub fn new() -> Self {
+        Self {
+            variant: Variant::Standard,
+            size: None,
+            win_length: None,
+            players: None,
+            starting_piece: None,
+            placements: Vec::new(),
+        }
+    }
+
+    /// The rule set, which decides the usual board size and win length
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Width and height of the board, 1 to 26
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Pieces in a row needed to win
+    pub fn win_length(mut self, win_length: usize) -> Self {
+        self.win_length = Some(win_length);
+        self
+    }
+
+    /// Who is playing, x and o if left out
+    pub fn players(mut self, players: Players) -> Self {
+        self.players = Some(players);
+        self
+    }
+
+    /// The piece that makes the first move, later turns follow the turn order from there
+    pub fn starting_piece(mut self, piece: Piece) -> Self {
+        self.starting_piece = Some(piece);
+        self
+    }
+
+    /// Puts a piece on the board before the first move
+    pub fn place(mut self, row: usize, col: usize, piece: Piece) -> Self {
+        self.placements.push((row, col, piece));
+        self
+    }
+
+    /// Sets the board size, pieces and player to move from board string notation (see Game::to_board_string)
+    /// Symbols are looked up in the players given so far, or the usual x and o
+    pub fn board_string(mut self, board: &str) -> Result<Self, SetupError> {
+        let players = match &self.players {
+            Some(players) => players.clone(),
+            None => Game::with_variant(self.variant).players.clone(),
+        };
+        let piece_for = |symbol| players.piece_for(symbol).ok_or(SetupError::UnknownSymbol {symbol});
+
+        // The board, then optionally the player to move
+        let mut parts = board.split_whitespace();
+        let rows: Vec<&str> = parts.next().ok_or(SetupError::InvalidBoardString)?.split(ROW_SEPARATOR).collect();
+        if let Some(side) = parts.next() {
+            let mut chars = side.chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => self.starting_piece = Some(piece_for(symbol)?),
+                _ => return Err(SetupError::InvalidBoardString),
+            }
+        }
+        if parts.next().is_some() {
+            return Err(SetupError::InvalidBoardString);
+        }
+
+        // Every row must be as long as there are rows
+        let size = rows.len();
+        for (row, tiles_row) in rows.iter().enumerate() {
+            if tiles_row.chars().count() != size {
+                return Err(SetupError::InvalidBoardString);
+            }
+            for (col, symbol) in tiles_row.chars().enumerate() {
+                if symbol != EMPTY_SYMBOL {
+                    self.placements.push((row, col, piece_for(symbol)?));
+                }
+            }
+        }
+
+        self.size = Some(size);
+        Ok(self)
+    }
+
+    /// Checks the setup is consistent and creates the game
+    pub fn build(self) -> Result<Game, SetupError> {
+        let default = Game::with_variant(self.variant);
+
+        // A bigger board keeps the usual win length unless another one was asked for
+        let size = self.size.unwrap_or(default.tiles.len());
+        let win_length = self.win_length.unwrap_or_else(|| default.win_length.min(size));
+        let players = self.players.unwrap_or_else(|| default.players.clone());
+        let mut game = Game::with_rules(self.variant, size, win_length, players)?;
+
+        // Start the turn order from the chosen piece
+        if let Some(piece) = self.starting_piece {
+            if self.variant == Variant::OrderAndChaos {
+                return Err(SetupError::UnsupportedStartingPiece {variant: self.variant});
+            }
+            game.turn = game.players.turn_order().iter().position(|&p| p == piece)
+                .ok_or(SetupError::UnknownPiece {piece})?;
+        }
+
+        // Place the pieces, checking each one lands on its own tile
+        for (row, col, piece) in self.placements {
+            if row >= size || col >= size {
+                return Err(SetupError::InvalidPosition {row, col});
+            }
+            else if piece.index() >= game.players.count() {
+                return Err(SetupError::UnknownPiece {piece});
+            }
+            else if game.tiles[row][col].is_some() {
+                return Err(SetupError::TilePlacedTwice {row, col});
+            }
+            game.tiles[row][col] = Some(piece);
+        }
+
+        // The board may already be decided, since update_winner only runs after a move
+        game.winner = game.scan_winner()?;
+        Ok(game)
+    }
+}
+
+// The forms games and players are saved in. Reading one back goes through the same checks as setting it up.
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::*;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename = "Players")]
+    pub struct PlayersRecord {
+        symbols: Vec<char>,
+        turn_order: Vec<Piece>,
+    }
+
+    impl From<Players> for PlayersRecord {
+        fn from(players: Players) -> Self {
+            Self {symbols: players.symbols, turn_order: players.turn_order}
+        }
+    }
+
+    impl TryFrom<PlayersRecord> for Players {
+        type Error = String;
+
+        fn try_from(record: PlayersRecord) -> Result<Self, Self::Error> {
+            let PlayersRecord {symbols, turn_order} = record;
+            Players::with_symbols(symbols)
+                .and_then(|players| players.with_turn_order(turn_order))
+                .map_err(|err| err.to_string())
+        }
+    }
+
+    // The winner is left out, it is worked out from the tiles when the game is read back
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename = "Game")]
+    pub struct GameRecord {
+        variant: Variant,
+        win_length: usize,
+        players: Players,
+        tiles: Tiles,
+        to_move: Piece,
+        history: Vec<Move>,
+        #[serde(default)]
+        timed_out: Vec<Piece>,
+    }
+
+    impl From<Game> for GameRecord {
+        fn from(game: Game) -> Self {
+            Self {
+                variant: game.variant,
+                win_length: game.win_length,
+                to_move: game.current_piece(),
+                players: game.players,
+                tiles: game.tiles,
+                history: game.history,
+                timed_out: game.timed_out,
+            }
+        }
+    }
+
+    impl TryFrom<GameRecord> for Game {
+        type Error = String;
+
+        fn try_from(record: GameRecord) -> Result<Self, Self::Error> {
+            let GameRecord {variant, win_length, players, tiles, to_move, history, timed_out} = record;
+            let setup_error = |err: SetupError| err.to_string();
+            let size = tiles.len();
+            let mut game = Game::with_rules(variant, size, win_length, players).map_err(setup_error)?;
+
+            // The board must be square and only hold the players' pieces
+            if tiles.iter().any(|row| row.len() != size) {
+                return Err(format!("Every row of the board must have {} tiles", size));
+            }
+            for piece in tiles.iter().flatten().flatten() {
+                if piece.index() >= game.players.count() {
+                    return Err(setup_error(SetupError::UnknownPiece {piece: *piece}));
+                }
+            }
+            game.turn = game.players.turn_order().iter().position(|&piece| piece == to_move)
+                .ok_or_else(|| setup_error(SetupError::UnknownPiece {piece: to_move}))?;
+
+            // Every move played must still be on the board, each on its own tile
+            let mut played = HashSet::new();
+            for mv in &history {
+                let on_board = tiles.get(mv.row).and_then(|row| row.get(mv.col)) == Some(&Some(mv.piece));
+                if !on_board || !played.insert((mv.row, mv.col)) {
+                    return Err(format!("The move on {} doesn't match the board", tile_name(mv.row, mv.col)));
+                }
+            }
+
+            // Players who ran out of time are still players of the game, but not the one to move
+            for piece in &timed_out {
+                if piece.index() >= game.players.count() || *piece == to_move && variant == Variant::Standard {
+                    return Err(setup_error(SetupError::UnknownPiece {piece: *piece}));
+                }
+            }
+
+            game.tiles = tiles;
+            game.moves_made = history.len();
+            game.history = history;
+            game.timed_out = timed_out;
+            game.winner = game.scan_winner().map_err(setup_error)?.or_else(|| game.time_out_winner());
+            Ok(game)
+        }
+    }
 }
 
 // Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    // A game with random rules, and the choices used to pick its moves
+    #[derive(Debug, Clone)]
+    struct Setup {
+        variant: Variant,
+        size: usize,
+        win_length: usize,
+        player_count: usize,
+        choices: Vec<usize>,
+    }
+
+    // Standard games from 3x3 to 5x5 with 2 or 3 players, or Order and Chaos on its usual board
+    fn setups() -> impl Strategy<Value = Setup> {
+        let standard = (3..=5usize, 2..=3usize).prop_flat_map(|(size, player_count)| {
+            (2..=size, prop::collection::vec(any::<usize>(), 0..=size * size)).prop_map(move |(win_length, choices)| Setup {
+                variant: Variant::Standard,
+                size,
+                win_length,
+                player_count,
+                choices,
+            })
+        });
+        let order_chaos = prop::collection::vec(any::<usize>(), 0..=36).prop_map(|choices| Setup {
+            variant: Variant::OrderAndChaos,
+            size: ORDER_AND_CHAOS_SIZE,
+            win_length: ORDER_AND_CHAOS_WIN_LENGTH,
+            player_count: 2,
+            choices,
+        });
+        prop_oneof![3 => standard, 1 => order_chaos]
+    }
+
+    impl Setup {
+        fn new_game(&self) -> Game {
+            let players = Players::new(self.player_count).unwrap();
+            Game::with_rules(self.variant, self.size, self.win_length, players).unwrap()
+        }
+    }
+
+    // Plays the legal move picked by `choice`. Returns false if the game is already over.
+    fn play_choice(game: &mut Game, choice: usize) -> bool {
+        let moves = game.legal_moves();
+        if moves.is_empty() {
+            return false;
+        }
+        game.play(moves[choice % moves.len()]).expect("legal moves can be played");
+        true
+    }
+
+    // Finds the winner by checking every line on the board, independently of Game::update_winner
+    fn brute_force_winner(game: &Game) -> Option<Winner> {
+        let tiles = game.tiles();
+        let size = tiles.len() as isize;
+        let length = game.win_length() as isize;
+        let mut lines = Vec::new();
+
+        for row in 0..size {
+            for col in 0..size {
+                for &(d_row, d_col) in &[(0, 1), (1, 0), (1, 1), (1, -1)] {
+                    let (end_row, end_col) = (row + d_row * (length - 1), col + d_col * (length - 1));
+                    if end_row < 0 || end_row >= size || end_col < 0 || end_col >= size {
+                        continue;
+                    }
+                    let line: Vec<Tile> = (0..length).map(|i| tiles[(row + d_row * i) as usize][(col + d_col * i) as usize]).collect();
+                    if line[0].is_some() && line.iter().all(|&tile| tile == line[0]) {
+                        lines.push(line[0].unwrap());
+                    }
+                }
+            }
+        }
+
+        let full = tiles.iter().flatten().all(Option::is_some);
+        match (game.variant(), lines.first(), full) {
+            (Variant::Standard, Some(&piece), _) => Some(Winner::Player(piece)),
+            (Variant::OrderAndChaos, Some(_), _) => Some(Winner::Order),
+            (Variant::Standard, None, true) => Some(Winner::Tie),
+            (Variant::OrderAndChaos, None, true) => Some(Winner::Chaos),
+            (_, None, false) => None,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn piece_counts_differ_by_at_most_one(setup in setups()) {
+            // In Order and Chaos both sides place either piece, so only standard games keep the counts even
+            prop_assume!(setup.variant == Variant::Standard);
+            let mut game = setup.new_game();
+
+            for &choice in &setup.choices {
+                if !play_choice(&mut game, choice) {
+                    break;
+                }
+                let mut counts = vec![0; setup.player_count];
+                for piece in game.tiles().iter().flatten().flatten() {
+                    counts[piece.index()] += 1;
+                }
+                let (min, max) = (counts.iter().min().unwrap(), counts.iter().max().unwrap());
+                prop_assert!(max - min <= 1, "counts {:?} on {}", counts, game.to_board_string());
+            }
+        }
+
+        #[test]
+        fn finished_games_reject_every_move(setup in setups()) {
+            let mut game = setup.new_game();
+            let mut choices = setup.choices.iter().copied().chain(0..);
+            while play_choice(&mut game, choices.next().unwrap()) {}
+            prop_assert!(game.is_finished());
+
+            let winner = game.winner();
+            for row in 0..setup.size {
+                for col in 0..setup.size {
+                    prop_assert_eq!(game.make_move(row, col), Err(MoveError::GameAlreadyOver));
+                    prop_assert_eq!(game.make_move_with(row, col, Piece::O), Err(MoveError::GameAlreadyOver));
+                }
+            }
+            prop_assert_eq!(game.winner(), winner);
+        }
+
+        #[test]
+        fn winner_agrees_with_brute_force(setup in setups()) {
+            let mut game = setup.new_game();
+            prop_assert_eq!(game.winner(), None);
+
+            for &choice in &setup.choices {
+                if !play_choice(&mut game, choice) {
+                    break;
+                }
+                prop_assert_eq!(game.winner(), brute_force_winner(&game), "{}", game.to_board_string());
+            }
+        }
+
+        #[test]
+        fn winning_line_is_a_line_of_one_piece(setup in setups()) {
+            let mut game = setup.new_game();
+            for &choice in &setup.choices {
+                if !play_choice(&mut game, choice) {
+                    break;
+                }
+            }
+
+            // There is a line exactly when brute force finds one
+            let has_line = matches!(brute_force_winner(&game), Some(Winner::Player(_)) | Some(Winner::Order));
+            prop_assert_eq!(game.winning_line().is_some(), has_line, "{}", game.to_board_string());
+
+            if let Some(((start_row, start_col), (end_row, end_col))) = game.winning_line() {
+                let steps = start_row.abs_diff(end_row).max(start_col.abs_diff(end_col));
+                prop_assert!(steps + 1 >= setup.win_length);
+
+                let step = |from: usize, to: usize| (to as isize - from as isize) / steps as isize;
+                let (d_row, d_col) = (step(start_row, end_row), step(start_col, end_col));
+                for i in 0..=steps as isize {
+                    let (row, col) = ((start_row as isize + d_row * i) as usize, (start_col as isize + d_col * i) as usize);
+                    prop_assert_eq!(game.tiles()[row][col], game.tiles()[start_row][start_col]);
+                }
+            }
+        }
+
+        #[test]
+        fn taken_tiles_report_their_piece(setup in setups(), pick in any::<usize>()) {
+            let mut game = setup.new_game();
+            for &choice in &setup.choices {
+                if !play_choice(&mut game, choice) {
+                    break;
+                }
+            }
+            prop_assume!(!game.is_finished() && !game.history().is_empty());
+
+            let Move {row, col, piece} = game.history()[pick % game.history().len()];
+            let expected = Err(MoveError::TileNotEmpty {other_piece: piece, row, col});
+            match game.variant() {
+                Variant::Standard => prop_assert_eq!(game.make_move(row, col), expected),
+                Variant::OrderAndChaos => prop_assert_eq!(game.make_move_with(row, col, Piece::X), expected),
+            }
+        }
+    }
+
+    #[test]
+    fn setup_errors_name_tiles_like_players_type_them() {
+        let err = GameBuilder::new().place(1, 1, Piece::X).place(1, 1, Piece::O).build().unwrap_err();
+        assert_eq!(err, SetupError::TilePlacedTwice {row: 1, col: 1});
+        assert_eq!(err.to_string(), "Two pieces were placed on 2B");
+        assert_eq!(GameBuilder::new().size(30).build().unwrap_err().to_string(), "The board must be 1 to 26 tiles wide, not 30");
+        assert_eq!(GameBuilder::new().place(0, 3, Piece::X).build().unwrap_err().to_string(), "There is no tile 1D on the board");
+    }
+
+    #[test]
+    fn move_errors_name_tiles_like_players_type_them() {
+        let mut game = Game::new();
+        assert_eq!(game.make_move(3, 0).unwrap_err().to_string(), "There is no tile 4A on the board!");
+        game.make_move(1, 1).unwrap();
+        assert_eq!(game.make_move(1, 1).unwrap_err().to_string(), "The tile at position 2B already has piece x in it!");
+    }
+
+    #[test]
+    fn running_out_of_time_takes_the_player_out() {
+        // With three players x drops out, and o and the third player keep taking turns
+        let mut game = Game::with_rules(Variant::Standard, 4, 3, Players::new(3).unwrap()).unwrap();
+        game.time_out().unwrap();
+        assert_eq!((game.winner(), game.current_piece(), game.timed_out()), (None, Piece::O, &[Piece::X][..]));
+        game.make_move(0, 0).unwrap();
+        assert_eq!(game.current_piece(), Piece::new(2));
+        game.make_move(0, 1).unwrap();
+        assert_eq!(game.current_piece(), Piece::O);
+
+        // Until only one of them is left
+        game.time_out().unwrap();
+        assert_eq!(game.winner(), Some(Winner::Player(Piece::new(2))));
+        assert_eq!(game.time_out(), Err(MoveError::GameAlreadyOver));
+
+        // With two players the other one wins straight away
+        let mut game = Game::new();
+        game.make_move(1, 1).unwrap();
+        game.time_out().unwrap();
+        assert_eq!(game.winner(), Some(Winner::Player(Piece::X)));
+    }
+
+    #[test]
+    fn moves_cant_be_taken_back_after_a_time_out() {
+        let mut game = Game::new();
+        game.make_move(1, 1).unwrap();
+        game.time_out().unwrap();
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.winner(), Some(Winner::Player(Piece::X)));
+        assert_eq!(game.history().len(), 1);
+
+        // Without a time out, undo reopens a finished game
+        let mut game = Game::new();
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+        assert_eq!(game.undo(), Some(Move {row: 0, col: 2, piece: Piece::X}));
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn observers_hear_events_in_order() {
+        let mut game = Game::new();
+        let events = game.events();
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let heard = Arc::clone(&names);
+        game.subscribe(move |event| heard.lock().unwrap().push(format!("{:?}", event)));
+
+        game.make_move(1, 1).unwrap();
+        game.make_move(1, 1).unwrap_err();
+        game.undo().unwrap();
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+
+        let events: Vec<GameEvent> = events.try_iter().collect();
+        let x = |row, col| Move {row, col, piece: Piece::X};
+        assert!(matches!(events[0], GameEvent::MovePlayed(mv) if mv == x(1, 1)));
+        assert!(matches!(events[1], GameEvent::MoveRejected(MoveError::TileNotEmpty {row: 1, col: 1, ..})));
+        assert!(matches!(events[2], GameEvent::Undo(mv) if mv == x(1, 1)));
+        assert!(matches!(events[7], GameEvent::MovePlayed(mv) if mv == x(0, 2)));
+        assert!(matches!(events[8], GameEvent::GameOver(Winner::Player(Piece::X))));
+        assert_eq!(events.len(), 9);
+
+        // Every observer hears the same events, and copies of the game don't bring their observers along
+        game.clone().undo();
+        assert_eq!(names.lock().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn builder_detects_boards_already_decided() {
+        let build = |board: &str| GameBuilder::new().board_string(board).unwrap().build();
+
+        // A line already on the board wins, and no more moves can be made
+        let mut won = build("xxx/oo./... o").unwrap();
+        assert_eq!(won.winner(), Some(Winner::Player(Piece::X)));
+        assert_eq!(won.make_move(2, 2), Err(MoveError::GameAlreadyOver));
+
+        // A full board without a line is a tie
+        assert_eq!(build("xox/xoo/oxx x").unwrap().winner(), Some(Winner::Tie));
+        assert_eq!(build("xo./.../... x").unwrap().winner(), None);
+
+        // Two players can't both have won already
+        assert_eq!(build("xxx/ooo/... x").unwrap_err(), SetupError::MultipleWinners);
+
+        // In Order and Chaos a full board goes to Chaos
+        let order_chaos = |board: &str| {
+            GameBuilder::new().variant(Variant::OrderAndChaos).size(3).win_length(3).board_string(board).unwrap().build()
+        };
+        assert_eq!(order_chaos("xox/xoo/oxx").unwrap().winner(), Some(Winner::Chaos));
+        assert_eq!(order_chaos("ooo/.../...").unwrap().winner(), Some(Winner::Order));
+    }
+
+    #[test]
+    fn order_and_chaos_players_choose_their_piece() {
+        let mut game = Game::with_variant(Variant::OrderAndChaos);
+        assert_eq!((game.tiles().len(), game.win_length()), (6, 5));
+        assert_eq!(game.current_role(), Some(Role::Order));
+        assert_eq!(game.make_move(0, 0), Err(MoveError::PieceRequired));
+
+        // Either side may place either piece, and the roles take turns
+        game.make_move_with(0, 0, Piece::O).unwrap();
+        assert_eq!(game.current_role(), Some(Role::Chaos));
+        game.make_move_with(5, 5, Piece::O).unwrap();
+        assert_eq!(game.current_role(), Some(Role::Order));
+        assert_eq!(game.legal_moves().len(), 34 * 2);
+        assert_eq!(game.make_move_with(1, 1, Piece::new(2)), Err(MoveError::WrongPiece {piece: Piece::new(2)}));
+    }
+
+    #[test]
+    fn order_wins_with_five_of_either_piece() {
+        // Order lines up five o's, whoever placed them
+        let mut game = Game::with_variant(Variant::OrderAndChaos);
+        for col in 0..5 {
+            game.make_move_with(0, col, Piece::O).unwrap();
+            if col < 4 {
+                game.make_move_with(5, col, Piece::X).unwrap();
+            }
+        }
+        assert_eq!(game.winner(), Some(Winner::Order));
+
+        // Six in a row counts as well, on the usual board there is no overline rule
+        let game = GameBuilder::new().variant(Variant::OrderAndChaos).board_string("xxxxxx/....../....../....../....../......").unwrap().build();
+        assert_eq!(game.unwrap().winner(), Some(Winner::Order));
+    }
+
+    #[test]
+    fn chaos_wins_a_full_board_without_a_line() {
+        // Alternating pairs never make five in a row in any direction
+        let rows = ["xxooxx", "ooxxoo", "xxooxx", "ooxxoo", "xxooxx", "ooxxoo"];
+        let mut game = Game::with_variant(Variant::OrderAndChaos);
+        for (row, pieces) in rows.iter().enumerate() {
+            for (col, symbol) in pieces.chars().enumerate() {
+                assert_eq!(game.winner(), None);
+                game.make_move_with(row, col, game.players().piece_for(symbol).unwrap()).unwrap();
+            }
+        }
+        assert_eq!(game.winner(), Some(Winner::Chaos));
+    }
+
+    #[test]
+    fn players_need_symbols_they_can_tell_apart() {
+        assert_eq!(Players::with_symbols(vec!['a']), Err(SetupError::TooFewPlayers {count: 1}));
+        assert_eq!(Players::with_symbols(vec!['a', 'b', 'a']), Err(SetupError::DuplicateSymbol {symbol: 'a'}));
+        for symbol in ['.', '/', ' '] {
+            assert_eq!(Players::with_symbols(vec!['a', symbol]), Err(SetupError::ReservedSymbol {symbol}));
+        }
+        assert_eq!(Players::new(9), Err(SetupError::TooManyPlayers {count: 9}));
+
+        let players = Players::new(3).unwrap();
+        assert_eq!((players.symbol(Piece::new(2)), players.piece_for('+'), players.piece_for('z')), ('+', Some(Piece::new(2)), None));
+    }
+
+    #[test]
+    fn turn_orders_list_every_player_once() {
+        let players = || Players::with_symbols(vec!['a', 'b', 'c']).unwrap();
+        let order = vec![Piece::new(2), Piece::new(0), Piece::new(1)];
+        assert_eq!(players().with_turn_order(order.clone()).unwrap().turn_order(), &order[..]);
+        assert_eq!(players().with_turn_order(vec![Piece::new(0), Piece::new(1)]), Err(SetupError::InvalidTurnOrder));
+        assert_eq!(players().with_turn_order(vec![Piece::new(0), Piece::new(0), Piece::new(1)]), Err(SetupError::InvalidTurnOrder));
+        assert_eq!(players().with_turn_order(vec![Piece::new(0), Piece::new(1), Piece::new(3)]), Err(SetupError::InvalidTurnOrder));
+    }
+
+    #[test]
+    fn three_players_take_turns_in_order() {
+        let players = Players::with_symbols(vec!['a', 'b', 'c']).unwrap()
+            .with_turn_order(vec![Piece::new(2), Piece::new(0), Piece::new(1)])
+            .unwrap();
+        let mut game = Game::with_rules(Variant::Standard, 4, 3, players).unwrap();
+        let mut turns = Vec::new();
+        for col in 0..4 {
+            turns.push(game.current_piece());
+            game.make_move(0, col).unwrap();
+        }
+        assert_eq!(turns, [Piece::new(2), Piece::new(0), Piece::new(1), Piece::new(2)]);
+        assert_eq!(game.to_board_string(), "cabc/..../..../.... a");
+    }
+}
@@ -0,0 +1,251 @@
+// Translations of everything the program says. Every message lives in a catalog per language under
+// locales/, one "key = text" line each, with {name} where a value is filled in. Messages about a count
+// have a line per plural form ("key.one", "key.other"), picked by the rules of the language.
+// The language comes from --lang, or else from LC_ALL, LC_MESSAGES or LANG, and falls back to English.
+
+// Standard Library Import Statements
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// A language with a catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+// Every language there is a catalog for, English first since it has every message
+pub const LANGUAGES: [Language; 2] = [Language::English, Language::Spanish];
+
+impl Language {
+    // The code used to pick the language, e.g. with --lang es
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    // Finds the language of a code or locale name, e.g. "es", "es-MX" or "es_ES.UTF-8"
+    pub fn from_code(code: &str) -> Option<Language> {
+        let code = code.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+        LANGUAGES.iter().copied().find(|language| language.code() == code)
+    }
+
+    // The language set in the environment, English if it isn't one with a catalog
+    // The first of LC_ALL, LC_MESSAGES and LANG that is set decides, like it does for other programs
+    fn from_environment() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_code(&value))
+            .unwrap_or(Language::English)
+    }
+
+    // The plural form a count takes: "one" or "other" (both languages so far only have these two)
+    fn plural_form(self, count: usize) -> &'static str {
+        match (self, count) {
+            (Language::English, 1) | (Language::Spanish, 1) => "one",
+            (Language::English, _) | (Language::Spanish, _) => "other",
+        }
+    }
+
+    // The catalog file, built into the program
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.txt"),
+            Language::Spanish => include_str!("../locales/es.txt"),
+        }
+    }
+
+    // The messages of the language, read from its catalog the first time they are needed
+    fn catalog(self) -> &'static Catalog {
+        static CATALOGS: [OnceLock<Catalog>; LANGUAGES.len()] = [OnceLock::new(), OnceLock::new()];
+        let index = LANGUAGES.iter().position(|&language| language == self).expect("every language is listed");
+        CATALOGS[index].get_or_init(|| Catalog::parse(self.source()).expect("the built in catalogs are valid"))
+    }
+}
+
+// The messages of one language, by key
+struct Catalog {
+    messages: HashMap<&'static str, &'static str>,
+}
+
+impl Catalog {
+    // Reads a catalog: "key = text" lines, with blank lines and lines starting with # skipped
+    fn parse(source: &'static str) -> Result<Catalog, String> {
+        let mut messages = HashMap::new();
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, text) = line.split_once('=').ok_or(format!("line {}: expected key = text", number + 1))?;
+            let (key, text) = (key.trim(), text.trim());
+            if key.is_empty() || text.is_empty() {
+                return Err(format!("line {}: expected key = text", number + 1));
+            }
+            if messages.insert(key, text).is_some() {
+                return Err(format!("line {}: {} is in the catalog twice", number + 1, key));
+            }
+        }
+        Ok(Catalog {messages})
+    }
+}
+
+// The language picked with --lang, or from the environment the first time a message is looked up
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+// Picks the language for the rest of the program, before anything is printed
+pub fn select(language: Language) {
+    let _ = LANGUAGE.set(language); // Only the first choice counts
+}
+
+// The language messages are in
+pub fn language() -> Language {
+    *LANGUAGE.get_or_init(Language::from_environment)
+}
+
+// Looks up a message and fills in its values, e.g. text("prompt.move", &[("example", &"1A")])
+// A message missing from a catalog is shown in English rather than not at all
+pub fn text(key: &str, values: &[(&str, &dyn Display)]) -> String {
+    let template = [language(), Language::English].iter()
+        .find_map(|language| language.catalog().messages.get(key).copied())
+        .unwrap_or(key);
+    fill(template, values)
+}
+
+// Looks up the form of a message that fits the count, which is filled in as {count}
+pub fn plural(key: &str, count: usize, values: &[(&str, &dyn Display)]) -> String {
+    let mut values = values.to_vec();
+    values.push(("count", &count));
+    text(&format!("{}.{}", key, language().plural_form(count)), &values)
+}
+
+// Replaces every {name} in a message with its value, leaving names without one as they are
+fn fill(template: &str, values: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            values.iter().find(|(name, _)| *name == &rest[1..end]).map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            },
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            },
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+// Looks up a message: t!("prompt.move", example = "1A")
+macro_rules! t {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::text($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),*])
+    };
+}
+
+// Looks up a message about a count: tn!("count.moves", 3) is "3 moves"
+macro_rules! tn {
+    ($key:expr, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::plural($key, $count, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),*])
+    };
+}
+
+pub(crate) use {t, tn};
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // Every source file of the program, so no lookup goes unchecked. The library has no messages of its own.
+    const SOURCES: [&str; 20] = [
+        include_str!("main.rs"), include_str!("accessible.rs"), include_str!("book.rs"), include_str!("bot.rs"),
+        include_str!("correspondence.rs"), include_str!("error.rs"), include_str!("event_log.rs"), include_str!("i18n.rs"),
+        include_str!("input.rs"), include_str!("machine.rs"), include_str!("messages.rs"), include_str!("parallel_solver.rs"),
+        include_str!("profiles.rs"), include_str!("puzzle.rs"), include_str!("scoreboard.rs"), include_str!("server.rs"),
+        include_str!("setup.rs"), include_str!("solver.rs"), include_str!("spectate.rs"), include_str!("symmetry.rs"),
+    ];
+
+    // The {names} a message fills in
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{').skip(1).filter_map(|part| part.split_once('}')).map(|(name, _)| name).collect()
+    }
+
+    // The keys looked up with t! and tn! in a source file, the plural ones with the forms they need
+    fn used_keys(source: &str, language: Language) -> Vec<String> {
+        let mut keys = Vec::new();
+        for (macro_name, plural) in [("t!(\"", false), ("tn!(\"", true)] {
+            for (start, _) in source.match_indices(macro_name) {
+                // Skip other macros ending the same way, like print!("
+                if source[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let key = source[start + macro_name.len()..].split('"').next().unwrap();
+                match plural {
+                    false => keys.push(key.to_string()),
+                    true => {
+                        let forms: BTreeSet<&str> = (0..100).map(|count| language.plural_form(count)).collect();
+                        keys.extend(forms.iter().map(|form| format!("{}.{}", key, form)));
+                    },
+                }
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        let english = Language::English.catalog();
+        for language in LANGUAGES {
+            let catalog = Catalog::parse(language.source()).unwrap_or_else(|err| panic!("{}: {}", language.code(), err));
+            let keys: BTreeSet<_> = catalog.messages.keys().collect();
+            let english_keys: BTreeSet<_> = english.messages.keys().collect();
+            assert_eq!(keys, english_keys, "{} has different keys than en", language.code());
+
+            // Translations fill in the same values, in whatever order the language needs them
+            for (key, text) in &catalog.messages {
+                assert_eq!(placeholders(text), placeholders(english.messages[key]), "{} {}", language.code(), key);
+            }
+
+            // Every message the program asks for exists, in every plural form the language uses
+            for source in SOURCES {
+                for key in used_keys(source, language) {
+                    assert!(catalog.messages.contains_key(key.as_str()), "{} is missing {}", language.code(), key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn languages_come_from_codes_and_locales() {
+        assert_eq!(Language::from_code("es"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("es_ES.UTF-8"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("en-GB"), Some(Language::English));
+        assert_eq!(Language::from_code("C"), None);
+        assert_eq!(Language::from_code("fr_FR"), None);
+    }
+
+    #[test]
+    fn messages_fill_in_values_and_plurals() {
+        assert_eq!(fill("Enter move (e.g. {example}):", &[("example", &"1A")]), "Enter move (e.g. 1A):");
+        assert_eq!(fill("{a} and {b} and {c}", &[("b", &2), ("a", &1)]), "1 and 2 and {c}");
+        assert_eq!(Language::English.plural_form(1), "one");
+        assert_eq!(Language::English.plural_form(0), "other");
+        assert_eq!(Language::Spanish.plural_form(2), "other");
+        assert_eq!(text("no.such.key", &[]), "no.such.key");
+    }
+}
@@ -0,0 +1,645 @@
+// Pictures of boards for slides and handouts: SVG, and PNG drawn pixel by pixel and encoded here,
+// so neither needs a system library. Boards are drawn with column letters above them and row numbers
+// down the side, the same as the text board.
+//
+// Pieces with the symbol x or o are drawn as a cross or a ring. Other symbols are written out as text in
+// SVG, and with a small built-in font in PNG (letters and digits only, anything else is a filled square).
+
+// Standard Library Import Statements
+use std::fmt::Write;
+
+// Package Import Statements
+use crate::game::{Game, Move, Piece, Players, Tiles};
+
+// Colors of the pieces, in piece order, repeating past the last one
+const PIECE_COLORS: [[u8; 3]; 6] = [
+    [0xc0, 0x39, 0x2b], // Red
+    [0x24, 0x71, 0xa3], // Blue
+    [0x1e, 0x84, 0x49], // Green
+    [0x7d, 0x3c, 0x98], // Purple
+    [0xb9, 0x77, 0x0e], // Orange
+    [0x56, 0x65, 0x73], // Slate
+];
+const BACKGROUND: [u8; 3] = [0xff, 0xff, 0xff];
+const GRID_COLOR: [u8; 3] = [0x44, 0x44, 0x44];
+const LABEL_COLOR: [u8; 3] = [0x55, 0x55, 0x55];
+const NUMBER_COLOR: [u8; 3] = [0x99, 0x99, 0x99];
+const STRIKE_COLOR: [u8; 3] = [0x22, 0x22, 0x22];
+
+// Tiles are 60 pixels wide unless set otherwise
+const DEFAULT_TILE_SIZE: u32 = 60;
+
+/// A picture of a board, optionally with a line struck through the winning pieces and the number of
+/// the move that placed each piece. Built up like a `GameBuilder`, then drawn with `to_svg` or `to_png`.
+///
+/// ```
+/// use tic_tac_toe::{BoardImage, Game};
+///
+/// let mut game = Game::new();
+/// for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+///     game.make_move(row, col).unwrap();
+/// }
+/// let image = BoardImage::from_game(&game).move_numbers(game.history());
+/// assert!(image.to_svg().starts_with("<svg"));
+/// assert!(image.to_png().starts_with(b"\x89PNG"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoardImage {
+    tiles: Tiles,
+    players: Players,
+    strike: Option<((usize, usize), (usize, usize))>,
+    numbers: Vec<Vec<Option<usize>>>, // The move number shown on each tile, counting from 1
+    tile_size: u32,
+}
+
+impl BoardImage {
+    /// Constructor for a picture of the pieces on `tiles`, with nothing marked
+    pub fn new(tiles: &Tiles, players: &Players) -> Self {
+        Self {
+            tiles: tiles.clone(),
+            players: players.clone(),
+            strike: None,
+            numbers: vec![vec![None; tiles.len()]; tiles.len()],
+            tile_size: DEFAULT_TILE_SIZE,
+        }
+    }
+
+    /// Constructor for a picture of a game's board, with its winning line struck through
+    pub fn from_game(game: &Game) -> Self {
+        let image = Self::new(game.tiles(), game.players());
+        match game.winning_line() {
+            Some((from, to)) => image.strike(from, to),
+            None => image,
+        }
+    }
+
+    /// Strikes a line through the tiles from one (row, col) to another
+    pub fn strike(mut self, from: (usize, usize), to: (usize, usize)) -> Self {
+        self.strike = Some((from, to));
+        self
+    }
+
+    /// Numbers each tile with the move that was played on it, the first move being 1
+    pub fn move_numbers(mut self, history: &[Move]) -> Self {
+        for (number, mv) in history.iter().enumerate() {
+            if let Some(tile) = self.numbers.get_mut(mv.row).and_then(|row| row.get_mut(mv.col)) {
+                *tile = Some(number + 1);
+            }
+        }
+        self
+    }
+
+    /// Sets the width of a tile in pixels
+    pub fn tile_size(mut self, pixels: u32) -> Self {
+        self.tile_size = pixels.max(10);
+        self
+    }
+
+    /// Draws the board as an SVG document
+    pub fn to_svg(&self) -> String {
+        let mut svg = self.svg_start();
+        for (row, tiles) in self.tiles.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if let Some(piece) = *tile {
+                    svg += &self.svg_piece(row, col, piece);
+                }
+                if let Some(number) = self.numbers[row][col] {
+                    svg += &self.svg_number(row, col, number);
+                }
+            }
+        }
+        if let Some((from, to)) = self.strike {
+            svg += &self.svg_strike(from, to);
+        }
+        svg + "</svg>\n"
+    }
+
+    /// Draws the board as a PNG file
+    pub fn to_png(&self) -> Vec<u8> {
+        let size = self.image_size();
+        let tile = self.tile_size as f64;
+        let mut canvas = Canvas::new(size, size);
+        self.draw_board(&mut canvas);
+
+        for (row, tiles) in self.tiles.iter().enumerate() {
+            for (col, piece) in tiles.iter().enumerate() {
+                let (x, y) = self.tile_origin(row, col);
+                if let Some(piece) = *piece {
+                    let color = piece_color(piece);
+                    let (center_x, center_y) = (x + tile / 2.0, y + tile / 2.0);
+                    match self.players.symbol(piece).to_ascii_lowercase() {
+                        'x' => {
+                            let inset = tile * 0.25;
+                            canvas.line(x + inset, y + inset, x + tile - inset, y + tile - inset, tile / 10.0, color);
+                            canvas.line(x + tile - inset, y + inset, x + inset, y + tile - inset, tile / 10.0, color);
+                        },
+                        'o' => canvas.ring(center_x, center_y, tile * 0.25, tile / 10.0, color),
+                        symbol => canvas.text(center_x, center_y, &symbol.to_string(), tile / 12.0, color),
+                    }
+                }
+                if let Some(number) = self.numbers[row][col] {
+                    let scale = (tile / 30.0).max(1.0);
+                    let width = text_width(&number.to_string(), scale);
+                    canvas.text(x + 4.0 + width / 2.0, y + 4.0 + scale * 2.5, &number.to_string(), scale, NUMBER_COLOR);
+                }
+            }
+        }
+
+        if let Some((from, to)) = self.strike {
+            let ((x1, y1), (x2, y2)) = (self.tile_center(from), self.tile_center(to));
+            canvas.line(x1, y1, x2, y2, tile / 8.0, STRIKE_COLOR);
+        }
+        canvas.to_png()
+    }
+
+    // Width and height of the whole picture: the board with half a tile of margin all round for the labels
+    fn image_size(&self) -> u32 {
+        self.tile_size * (self.tiles.len() as u32 + 1)
+    }
+
+    // Top left corner of a tile
+    fn tile_origin(&self, row: usize, col: usize) -> (f64, f64) {
+        let tile = self.tile_size as f64;
+        (tile / 2.0 + col as f64 * tile, tile / 2.0 + row as f64 * tile)
+    }
+
+    fn tile_center(&self, (row, col): (usize, usize)) -> (f64, f64) {
+        let (x, y) = self.tile_origin(row, col);
+        let half = self.tile_size as f64 / 2.0;
+        (x + half, y + half)
+    }
+
+    // The document up to the pieces: background, labels and grid
+    fn svg_start(&self) -> String {
+        let size = self.image_size();
+        let tile = self.tile_size as f64;
+        let count = self.tiles.len();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\" font-family=\"sans-serif\">\n",
+            size,
+        );
+        writeln!(svg, "<rect width=\"{0}\" height=\"{0}\" fill=\"{1}\"/>", size, hex(BACKGROUND)).expect("writing to a String can't fail");
+
+        // Column letters and row numbers, half way into the margin
+        for i in 0..count {
+            let (x, y) = self.tile_center((i, i));
+            let label = |x: f64, y: f64, text: &str| format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>\n",
+                x, y, tile * 0.3, hex(LABEL_COLOR), text,
+            );
+            svg += &label(x, tile / 4.0, &column_label(i));
+            svg += &label(tile / 4.0, y, &(i + 1).to_string());
+        }
+
+        // Grid lines around every tile
+        let (start, end) = (tile / 2.0, tile / 2.0 + count as f64 * tile);
+        for i in 0..=count {
+            let at = start + i as f64 * tile;
+            writeln!(
+                svg,
+                "<path d=\"M{1:.1} {0:.1}H{2:.1}M{0:.1} {1:.1}V{2:.1}\" stroke=\"{3}\" stroke-width=\"2\"/>",
+                at, start, end, hex(GRID_COLOR),
+            ).expect("writing to a String can't fail");
+        }
+        svg
+    }
+
+    // A cross, a ring, or the player's symbol
+    fn svg_piece(&self, row: usize, col: usize, piece: Piece) -> String {
+        let tile = self.tile_size as f64;
+        let (x, y) = self.tile_origin(row, col);
+        let (center_x, center_y) = self.tile_center((row, col));
+        let color = hex(piece_color(piece));
+
+        match self.players.symbol(piece).to_ascii_lowercase() {
+            'x' => {
+                let (near, far) = (tile * 0.25, tile * 0.75);
+                format!(
+                    "<path d=\"M{:.1} {:.1}L{:.1} {:.1}M{:.1} {:.1}L{:.1} {:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\" stroke-linecap=\"round\"/>\n",
+                    x + near, y + near, x + far, y + far, x + far, y + near, x + near, y + far, color, tile / 10.0,
+                )
+            },
+            'o' => format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\"/>\n",
+                center_x, center_y, tile * 0.25, color, tile / 10.0,
+            ),
+            _ => format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>\n",
+                center_x, center_y, tile * 0.6, color, escape(self.players.symbol(piece)),
+            ),
+        }
+    }
+
+    // A small move number in the top left corner of a tile
+    fn svg_number(&self, row: usize, col: usize, number: usize) -> String {
+        let tile = self.tile_size as f64;
+        let (x, y) = self.tile_origin(row, col);
+        format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" fill=\"{}\">{}</text>\n",
+            x + tile * 0.08, y + tile * 0.25, tile * 0.2, hex(NUMBER_COLOR), number,
+        )
+    }
+
+    // A line through the centers of the tiles at either end of a winning line
+    fn svg_strike(&self, from: (usize, usize), to: (usize, usize)) -> String {
+        let ((x1, y1), (x2, y2)) = (self.tile_center(from), self.tile_center(to));
+        format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\" stroke-linecap=\"round\" opacity=\"0.8\"/>\n",
+            x1, y1, x2, y2, hex(STRIKE_COLOR), self.tile_size as f64 / 8.0,
+        )
+    }
+
+    // Background, labels and grid of the PNG
+    fn draw_board(&self, canvas: &mut Canvas) {
+        let tile = self.tile_size as f64;
+        let count = self.tiles.len();
+        let scale = (tile / 20.0).max(1.0);
+
+        for i in 0..count {
+            let (x, y) = self.tile_center((i, i));
+            canvas.text(x, tile / 4.0, &column_label(i), scale, LABEL_COLOR);
+            canvas.text(tile / 4.0, y, &(i + 1).to_string(), scale, LABEL_COLOR);
+        }
+
+        let (start, end) = (tile / 2.0, tile / 2.0 + count as f64 * tile);
+        for i in 0..=count {
+            let at = start + i as f64 * tile;
+            canvas.rect(start - 1.0, at - 1.0, end + 1.0, at + 1.0, GRID_COLOR);
+            canvas.rect(at - 1.0, start - 1.0, at + 1.0, end + 1.0, GRID_COLOR);
+        }
+    }
+}
+
+/// One picture per position of a game: before the first move, then after every move.
+/// Pieces placed before the game started are on every picture, and the last one strikes through the winning line.
+pub fn game_frames(game: &Game) -> Vec<BoardImage> {
+    let history = game.history();
+    let mut tiles = game.tiles().clone();
+    for mv in history {
+        tiles[mv.row][mv.col] = None;
+    }
+
+    let mut frames = vec![BoardImage::new(&tiles, game.players())];
+    for mv in history {
+        tiles[mv.row][mv.col] = Some(mv.piece);
+        frames.push(BoardImage::new(&tiles, game.players()));
+    }
+    if let Some((from, to)) = game.winning_line() {
+        let last = frames.pop().expect("there is always a first frame");
+        frames.push(last.strike(from, to));
+    }
+    frames
+}
+
+/// An SVG that plays through a game by itself, adding a move every `seconds_per_move` seconds
+/// and striking through the winning line at the end. The move numbers are shown if `move_numbers` is set.
+pub fn animated_svg(game: &Game, seconds_per_move: f64, move_numbers: bool) -> String {
+    let history = game.history();
+    let image = game_frames(game).swap_remove(0);
+
+    // Everything on the board before the first move is there from the start
+    let mut svg = image.svg_start();
+    for (row, tiles) in image.tiles.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if let Some(piece) = *tile {
+                svg += &image.svg_piece(row, col, piece);
+            }
+        }
+    }
+
+    // Every move appears in turn, hidden until its time comes
+    let appear = |time: f64, content: String| format!(
+        "<g visibility=\"hidden\"><set attributeName=\"visibility\" to=\"visible\" begin=\"{:.2}s\" fill=\"freeze\"/>\n{}</g>\n",
+        time, content,
+    );
+    for (number, mv) in history.iter().enumerate() {
+        let mut content = image.svg_piece(mv.row, mv.col, mv.piece);
+        if move_numbers {
+            content += &image.svg_number(mv.row, mv.col, number + 1);
+        }
+        svg += &appear((number + 1) as f64 * seconds_per_move, content);
+    }
+    if let Some((from, to)) = game.winning_line() {
+        svg += &appear((history.len() + 1) as f64 * seconds_per_move, image.svg_strike(from, to));
+    }
+    svg + "</svg>\n"
+}
+
+// Pieces past the last color start again from the first
+fn piece_color(piece: Piece) -> [u8; 3] {
+    PIECE_COLORS[piece.index() % PIECE_COLORS.len()]
+}
+
+// A color as SVG writes it, e.g. #c0392b
+fn hex([red, green, blue]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+// Column letters as the text board writes them
+fn column_label(col: usize) -> String {
+    ((b'A' + col as u8) as char).to_string()
+}
+
+// Keeps symbols like < and & from breaking the document
+fn escape(symbol: char) -> String {
+    match symbol {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        '"' => "&quot;".to_string(),
+        symbol => symbol.to_string(),
+    }
+}
+
+// An RGB picture being drawn for a PNG
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Self {width, height, pixels: vec![BACKGROUND; (width * height) as usize]}
+    }
+
+    // Colors every pixel whose center is inside `inside`, looking only within the given box
+    fn fill<F: Fn(f64, f64) -> bool>(&mut self, left: f64, top: f64, right: f64, bottom: f64, color: [u8; 3], inside: F) {
+        let clamp = |value: f64, max: u32| value.max(0.0).min(max as f64) as u32;
+        for y in clamp(top.floor(), self.height)..clamp(bottom.ceil(), self.height) {
+            for x in clamp(left.floor(), self.width)..clamp(right.ceil(), self.width) {
+                if inside(x as f64 + 0.5, y as f64 + 0.5) {
+                    self.pixels[(y * self.width + x) as usize] = color;
+                }
+            }
+        }
+    }
+
+    fn rect(&mut self, left: f64, top: f64, right: f64, bottom: f64, color: [u8; 3]) {
+        self.fill(left, top, right, bottom, color, |_, _| true);
+    }
+
+    // A line `width` pixels thick with round ends
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, width: f64, color: [u8; 3]) {
+        let radius = width / 2.0;
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length_squared = (dx * dx + dy * dy).max(f64::EPSILON);
+
+        self.fill(x1.min(x2) - radius, y1.min(y2) - radius, x1.max(x2) + radius, y1.max(y2) + radius, color, |x, y| {
+            // Distance from the nearest point on the line
+            let along = (((x - x1) * dx + (y - y1) * dy) / length_squared).clamp(0.0, 1.0);
+            let (near_x, near_y) = (x1 + along * dx, y1 + along * dy);
+            (x - near_x).powi(2) + (y - near_y).powi(2) <= radius * radius
+        });
+    }
+
+    // A circle outline `width` pixels thick, centered on the circle of radius `radius`
+    fn ring(&mut self, center_x: f64, center_y: f64, radius: f64, width: f64, color: [u8; 3]) {
+        let (inner, outer) = (radius - width / 2.0, radius + width / 2.0);
+        self.fill(center_x - outer, center_y - outer, center_x + outer, center_y + outer, color, |x, y| {
+            let distance = ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt();
+            distance >= inner && distance <= outer
+        });
+    }
+
+    // Text in the built-in font, centered on (center_x, center_y), each font pixel `scale` pixels wide
+    fn text(&mut self, center_x: f64, center_y: f64, text: &str, scale: f64, color: [u8; 3]) {
+        let left = center_x - text_width(text, scale) / 2.0;
+        let top = center_y - scale * GLYPH_HEIGHT as f64 / 2.0;
+
+        for (i, c) in text.chars().enumerate() {
+            let x = left + i as f64 * scale * (GLYPH_WIDTH + 1) as f64;
+            let glyph = match glyph(c) {
+                Some(glyph) => glyph,
+                None => {
+                    self.rect(x, top, x + scale * GLYPH_WIDTH as f64, top + scale * GLYPH_HEIGHT as f64, color);
+                    continue;
+                },
+            };
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        let (pixel_x, pixel_y) = (x + col as f64 * scale, top + row as f64 * scale);
+                        self.rect(pixel_x, pixel_y, pixel_x + scale, pixel_y + scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    // Encodes the picture as an 8 bit RGB PNG. The image data is stored without compression,
+    // which is still a valid zlib stream and keeps the encoder short.
+    fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) as usize * self.height as usize);
+        for row in self.pixels.chunks(self.width as usize) {
+            raw.push(0); // No filter
+            for pixel in row {
+                raw.extend_from_slice(pixel);
+            }
+        }
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut header = Vec::new();
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bits per channel, RGB, standard compression, filters and no interlacing
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+// Appends a chunk: its length, type, data and a CRC of the type and data
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Wraps data in a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let length = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&length.to_le_bytes());
+        out.extend_from_slice(&(!length).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+// The CRC-32 PNG chunks end with
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// The checksum zlib streams end with
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// The built-in font: 3x5 pixel digits and capital letters, one row of bits per line
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const DIGITS: [[u8; GLYPH_HEIGHT]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const LETTERS: [[u8; GLYPH_HEIGHT]; 26] = [
+    [0b010, 0b101, 0b111, 0b101, 0b101], // A
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b110, 0b100, 0b111], // E
+    [0b111, 0b100, 0b110, 0b100, 0b100],
+    [0b011, 0b100, 0b101, 0b101, 0b011],
+    [0b101, 0b101, 0b111, 0b101, 0b101],
+    [0b111, 0b010, 0b010, 0b010, 0b111],
+    [0b001, 0b001, 0b001, 0b101, 0b010], // J
+    [0b101, 0b101, 0b110, 0b101, 0b101],
+    [0b100, 0b100, 0b100, 0b100, 0b111],
+    [0b101, 0b111, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b101, 0b101, 0b101],
+    [0b010, 0b101, 0b101, 0b101, 0b010], // O
+    [0b110, 0b101, 0b110, 0b100, 0b100],
+    [0b010, 0b101, 0b101, 0b110, 0b011],
+    [0b110, 0b101, 0b110, 0b101, 0b101],
+    [0b011, 0b100, 0b010, 0b001, 0b110],
+    [0b111, 0b010, 0b010, 0b010, 0b010], // T
+    [0b101, 0b101, 0b101, 0b101, 0b111],
+    [0b101, 0b101, 0b101, 0b101, 0b010],
+    [0b101, 0b101, 0b111, 0b111, 0b101],
+    [0b101, 0b101, 0b010, 0b101, 0b101],
+    [0b101, 0b101, 0b010, 0b010, 0b010], // Y
+    [0b111, 0b001, 0b010, 0b100, 0b111],
+];
+
+// Looks up a character in the font, ignoring case
+fn glyph(c: char) -> Option<&'static [u8; GLYPH_HEIGHT]> {
+    match c.to_ascii_uppercase() {
+        c @ '0'..='9' => Some(&DIGITS[c as usize - '0' as usize]),
+        c @ 'A'..='Z' => Some(&LETTERS[c as usize - 'A' as usize]),
+        _ => None,
+    }
+}
+
+// Width of text in the built-in font, with a font pixel of space between characters
+fn text_width(text: &str, scale: f64) -> f64 {
+    let count = text.chars().count();
+    (count * (GLYPH_WIDTH + 1)).saturating_sub(1) as f64 * scale
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A game x won along the top row
+    fn won_game() -> Game {
+        let mut game = Game::new();
+        for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+        game
+    }
+
+    // Splits a PNG into its chunks, checking every CRC on the way
+    fn png_chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            let (body, crc) = rest[4..8 + length + 4].split_at(4 + length);
+            assert_eq!(crc32(body).to_be_bytes(), crc);
+            chunks.push((String::from_utf8(body[..4].to_vec()).unwrap(), body[4..].to_vec()));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn png_holds_every_pixel() {
+        let image = BoardImage::from_game(&won_game()).tile_size(20);
+        let chunks = png_chunks(&image.to_png());
+        let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+        assert_eq!(&chunks[0].1[..8], &[0, 0, 0, 80, 0, 0, 0, 80]);
+
+        // A filter byte and three bytes per pixel on every row, stored in blocks of up to 65535 bytes
+        let raw: usize = 80 * (1 + 80 * 3);
+        let blocks = raw.div_ceil(0xffff);
+        assert_eq!(chunks[1].1.len(), 2 + blocks * 5 + raw + 4);
+    }
+
+    #[test]
+    fn svg_marks_the_win_and_the_moves() {
+        let game = won_game();
+        let svg = BoardImage::from_game(&game).move_numbers(game.history()).to_svg();
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains(">5</text>"));
+
+        let plain = BoardImage::new(game.tiles(), game.players()).to_svg();
+        assert!(!plain.contains("<line") && !plain.contains(">5</text>"));
+
+        let players = Players::with_symbols(vec!['<', '&']).unwrap();
+        let mut tiles = vec![vec![None; 3]; 3];
+        tiles[0][0] = Some(Piece::X);
+        tiles[1][1] = Some(Piece::O);
+        let svg = BoardImage::new(&tiles, &players).to_svg();
+        assert!(svg.contains(">&lt;</text>") && svg.contains(">&amp;</text>"));
+    }
+
+    #[test]
+    fn frames_replay_the_game() {
+        let game = won_game();
+        let frames = game_frames(&game);
+        assert_eq!(frames.len(), 6);
+        assert!(frames[0].tiles.iter().flatten().all(Option::is_none));
+        assert_eq!(&frames[5].tiles, game.tiles());
+        assert!(frames[..5].iter().all(|frame| frame.strike.is_none()));
+        assert_eq!(frames[5].strike, Some(((0, 0), (0, 2))));
+
+        let animated = animated_svg(&game, 0.5, true);
+        assert_eq!(animated.matches("<set").count(), 6);
+        assert!(animated.contains("begin=\"3.00s\""));
+    }
+}
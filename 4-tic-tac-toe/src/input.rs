@@ -0,0 +1,83 @@
+// Keyboard input for the interactive prompts. Lines are read on a thread of their own and Ctrl-C is
+// caught, so a prompt waiting for a line can be told the player quit and the game can offer to save
+// before the program exits. Nothing is read or caught until the first prompt asks for a line.
+
+// Standard Library Import Statements
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+
+// Package Import Statements
+use crate::error::Error;
+use crate::i18n::t;
+
+// What reached a prompt
+enum Input {
+    Line(String),
+    End,                // Stdin closed, e.g. Ctrl-D or the end of a piped file
+    Failed(io::Error),  // Reading stdin failed
+    Interrupted,        // Ctrl-C
+}
+
+// Where the prompts get their input, and whether stdin has already run out
+struct Keyboard {
+    receiver: Receiver<Input>,
+    ended: bool,
+}
+
+static KEYBOARD: OnceLock<Mutex<Keyboard>> = OnceLock::new();
+
+// Reads the next line typed, without its line ending
+pub fn read_line() -> Result<String, Error> {
+    let mut keyboard = KEYBOARD.get_or_init(start).lock().expect("no prompt panics while reading");
+    if keyboard.ended {
+        return Err(Error::EndOfInput);
+    }
+
+    match keyboard.receiver.recv() {
+        Ok(Input::Line(line)) => Ok(line),
+        Ok(Input::Interrupted) => Err(Error::Interrupted),
+
+        // Nothing more will be read after stdin closes or fails
+        Ok(Input::Failed(err)) => {
+            keyboard.ended = true;
+            Err(err.into())
+        },
+        Ok(Input::End) | Err(_) => {
+            keyboard.ended = true;
+            Err(Error::EndOfInput)
+        },
+    }
+}
+
+// Starts reading stdin and catching Ctrl-C, both sending what they get to the same channel
+fn start() -> Mutex<Keyboard> {
+    let (sender, receiver) = mpsc::channel();
+
+    let interrupts = sender.clone();
+    if let Err(err) = ctrlc::set_handler(move || {
+        let _ = interrupts.send(Input::Interrupted);
+    }) {
+        eprintln!("{}", t!("input.no_interrupt", error = err));
+    }
+
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        let input = loop {
+            match lines.next() {
+                Some(Ok(line)) => {
+                    if sender.send(Input::Line(line.trim_end().to_string())).is_err() {
+                        return;
+                    }
+                },
+                Some(Err(err)) => break Input::Failed(err),
+                None => break Input::End,
+            }
+        };
+        let _ = sender.send(input);
+    });
+
+    Mutex::new(Keyboard {receiver, ended: false})
+}
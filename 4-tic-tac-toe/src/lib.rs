@@ -0,0 +1,48 @@
+//! Tic Tac Toe for 2 or more players, on boards up to 26x26, plus the Order and Chaos variant.
+//!
+//! This is the game itself, without any of the prompts: the `tic-tac-toe` binary, its bots and its
+//! server are all built on it, and other tools can be too.
+//! Boards can be drawn as text (`render`) or as SVG and PNG pictures (`image`), and timed games keep
+//! their chess clocks in `clock`.
+//!
+//! ```
+//! use tic_tac_toe::{parse_move, render_game, Game, MoveError, Piece, Winner};
+//!
+//! let mut game = Game::new();
+//! for input in ["1A", "2A", "1B", "2B"] {
+//!     let (row, col, _) = parse_move(input, game.tiles().len(), game.players()).unwrap();
+//!     game.make_move(row, col).unwrap();
+//! }
+//!
+//! // Taken tiles are reported in the same notation the players type
+//! let err = game.make_move(1, 1).unwrap_err();
+//! assert!(matches!(err, MoveError::TileNotEmpty {other_piece: Piece::O, ..}));
+//! assert_eq!(err.to_string(), "The tile at position 2B already has piece o in it!");
+//!
+//! game.make_move(0, 2).unwrap();
+//! assert_eq!(game.winner(), Some(Winner::Player(Piece::X)));
+//! print!("{}", render_game(&game));
+//! ```
+//!
+//! # Features
+//!
+//! - `serde` (on by default): `Serialize` and `Deserialize` for the game types. Games are checked as they
+//!   are read back, and the winner is worked out from the board rather than read from the input. The
+//!   `tic-tac-toe` binary needs it; use `default-features = false` for the library without serde.
+//!
+//! # Stability
+//!
+//! Everything re-exported here follows semver. Error and event enums are `#[non_exhaustive]`, so new
+//! kinds of error or event can be added in a minor release: match them with a wildcard arm.
+
+pub mod clock;
+pub mod game;
+pub mod image;
+pub mod notation;
+pub mod render;
+
+pub use clock::{Clocks, TimeControl};
+pub use game::{Game, GameBuilder, GameEvent, Move, MoveError, Piece, Players, Role, SetupError, Tile, Tiles, Variant, Winner};
+pub use image::{animated_svg, game_frames, BoardImage};
+pub use notation::{move_name, parse_move, tile_name, InvalidMove, InvalidMoveReason, MoveParser};
+pub use render::{describe_tile, describe_tile_with, describe_tiles, describe_tiles_with, render_game, render_tiles};
@@ -0,0 +1,190 @@
+// Machine interface for scripts and test harnesses: one JSON command per input line,
+// one JSON object per output line. Commands are
+//   {"move":"2B"}   plays a move, written the same way a player would type it ("3D o" in Order and Chaos)
+//   {"undo":true}   takes back the last move (and the computer's reply, if it is playing)
+//   {"state":true}  reports the game without changing it
+// Every reply is the state of the game, with an "error" object added when the command failed.
+// In a timed game the clock of the side to move runs between replies, and a side that runs out of time
+// loses when the next command arrives. Timed games can't take moves back.
+
+// Standard Library Import Statements
+use std::io::{self, BufRead, Write};
+
+// Package Import Statements
+use tic_tac_toe::clock::Clocks;
+use tic_tac_toe::game::{Game, MoveError, Piece, Players, Role, Variant, Winner, EMPTY_SYMBOL};
+use tic_tac_toe::{move_name, parse_move, tile_name};
+use serde_json::{json, Value};
+use crate::bot::Bot;
+use crate::setup::current_seat;
+
+// Reads commands from stdin until it closes, starting with a reply describing the new game
+pub fn run(game: &mut Game, bot: &mut Bot, bot_seat: Option<usize>, mut clocks: Option<Clocks>) -> io::Result<()> {
+    let reply = bot_reply(game, bot, bot_seat, &mut clocks);
+    respond(game, clocks.as_ref(), reply)?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // The flag may have fallen while we waited for the command
+        if let Some(clocks) = clocks.as_mut() {
+            clocks.check(game);
+        }
+        let reply = command(game, bot, bot_seat, &mut clocks, &line);
+        respond(game, clocks.as_ref(), reply)?;
+    }
+    Ok(())
+}
+
+// Writes the full state after every command, so callers never have to track it themselves
+fn respond(game: &Game, clocks: Option<&Clocks>, reply: Result<(), Value>) -> io::Result<()> {
+    let mut response = state(game, clocks);
+    if let (Value::Object(fields), Err(error)) = (&mut response, reply) {
+        fields.insert("error".to_string(), error);
+    }
+
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{}", response)?;
+    stdout.flush()
+}
+
+// Runs one command, returning the error to report if it failed
+fn command(game: &mut Game, bot: &mut Bot, bot_seat: Option<usize>, clocks: &mut Option<Clocks>, line: &str) -> Result<(), Value> {
+    let command: Value = serde_json::from_str(line).map_err(|err| error("InvalidJson", vec![("message", err.to_string().into())]))?;
+
+    if let Some(input) = command.get("move") {
+        let input = input.as_str().ok_or_else(|| invalid_command("\"move\" must be a string, e.g. {\"move\":\"2B\"}"))?;
+        let (row, col, piece) = parse_move(input, game.tiles().len(), game.players())
+            .map_err(|err| error("InvalidMove", vec![("input", input.into()), ("message", err.reason.to_string().into())]))?;
+        let result = match piece {
+            Some(piece) => game.make_move_with(row, col, piece),
+            None => game.make_move(row, col),
+        };
+        result.map_err(|err| move_error(game.players(), &err))?;
+        if let Some(clocks) = clocks.as_mut() {
+            clocks.switch(game);
+        }
+        bot_reply(game, bot, bot_seat, clocks)
+    }
+    else if command.get("undo").and_then(Value::as_bool) == Some(true) {
+        // Taking a move back would give its time back too
+        if clocks.is_some() {
+            return Err(error("UndoNotAllowed", vec![("message", "moves can't be taken back in a timed game".into())]));
+        }
+        game.undo().ok_or_else(|| error("NothingToUndo", vec![]))?;
+
+        // Take back the computer's move as well, so it is the caller's turn again
+        if bot_seat.is_some() && bot_seat == Some(current_seat(game, 0)) {
+            game.undo();
+        }
+        bot_reply(game, bot, bot_seat, clocks)
+    }
+    else if command.get("state").and_then(Value::as_bool) == Some(true) {
+        Ok(())
+    }
+    else {
+        Err(invalid_command("expected {\"move\":...}, {\"undo\":true} or {\"state\":true}"))
+    }
+}
+
+// Lets the computer move if it is its turn. Its clock runs while it thinks, like everyone else's.
+fn bot_reply(game: &mut Game, bot: &mut Bot, bot_seat: Option<usize>, clocks: &mut Option<Clocks>) -> Result<(), Value> {
+    if game.is_finished() || bot_seat.is_none() || bot_seat != Some(current_seat(game, 0)) {
+        return Ok(());
+    }
+    let (mv, _) = bot.choose_move(game).map_err(|err| error("BotFailed", vec![("message", err.to_string().into())]))?;
+    if let Some(clocks) = clocks.as_mut() {
+        if clocks.check(game) {
+            return Ok(());
+        }
+    }
+    game.play(mv).expect("the bot only picks legal moves");
+    if let Some(clocks) = clocks.as_mut() {
+        clocks.switch(game);
+    }
+    Ok(())
+}
+
+// Describes a game: every player's symbol (in piece order), the board (one string per row, top to bottom),
+// who moves next, the winner, every legal move, every move played so far and, in a timed game, the
+// milliseconds every side has left (in piece order, or Order then Chaos)
+pub fn state(game: &Game, clocks: Option<&Clocks>) -> Value {
+    let players = game.players();
+    let board: Vec<String> = game.tiles().iter()
+        .map(|row| row.iter().map(|tile| tile.map_or(EMPTY_SYMBOL, |piece| players.symbol(piece))).collect())
+        .collect();
+
+    // Standard games are played piece by piece, Order and Chaos role by role
+    let (to_move, role) = match (game.is_finished(), game.current_role()) {
+        (true, _) => (None, None),
+        (false, Some(Role::Order)) => (None, Some("order")),
+        (false, Some(Role::Chaos)) => (None, Some("chaos")),
+        (false, None) => (Some(players.symbol(game.current_piece()).to_string()), None),
+    };
+    let winner = game.winner().map(|winner| winner_name(players, winner));
+    let variant = match game.variant() {
+        Variant::Standard => "standard",
+        Variant::OrderAndChaos => "order_and_chaos",
+    };
+
+    let symbols: Vec<String> = (0..players.count()).map(|index| players.symbol(Piece::new(index)).to_string()).collect();
+    let clocks_ms: Option<Vec<usize>> = clocks.map(|clocks| {
+        (0..clocks.sides()).map(|side| clocks.remaining(side).as_millis() as usize).collect()
+    });
+
+    json!({
+        "variant": variant,
+        "symbols": symbols,
+        "board": board,
+        "to_move": to_move,
+        "role": role,
+        "winner": winner,
+        "legal_moves": game.legal_moves().into_iter().map(|mv| move_name(game, mv)).collect::<Vec<_>>(),
+        "history": game.history().iter().map(|&mv| move_name(game, mv)).collect::<Vec<_>>(),
+        "clocks_ms": clocks_ms,
+    })
+}
+
+// Describes a rejected move, keeping the fields of the MoveError (rows and columns count from 0)
+pub fn move_error(players: &Players, err: &MoveError) -> Value {
+    let symbol = |piece| Value::from(players.symbol(piece).to_string());
+    let tile = |row, col| Value::from(tile_name(row, col));
+
+    match *err {
+        MoveError::GameAlreadyOver => error("GameAlreadyOver", vec![]),
+        MoveError::InvalidPosition {row, col} => error("InvalidPosition", vec![("row", row.into()), ("col", col.into())]),
+        MoveError::TileNotEmpty {other_piece, row, col} => error("TileNotEmpty", vec![
+            ("row", row.into()),
+            ("col", col.into()),
+            ("tile", tile(row, col)),
+            ("other_piece", symbol(other_piece)),
+        ]),
+        MoveError::WrongPiece {piece} => error("WrongPiece", vec![("piece", symbol(piece))]),
+        MoveError::PieceRequired => error("PieceRequired", vec![]),
+        ref other => error("MoveRejected", vec![("message", other.with_symbols(players).to_string().into())]),
+    }
+}
+
+// Names a winner by their symbol, or "order", "chaos" or "tie"
+pub fn winner_name(players: &Players, winner: Winner) -> String {
+    match winner {
+        Winner::Player(piece) => players.symbol(piece).to_string(),
+        Winner::Order => "order".to_string(),
+        Winner::Chaos => "chaos".to_string(),
+        Winner::Tie => "tie".to_string(),
+    }
+}
+
+// Builds an error object: its kind followed by any details
+pub fn error(kind: &str, details: Vec<(&str, Value)>) -> Value {
+    let mut fields = vec![("kind", kind.into())];
+    fields.extend(details);
+    Value::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+fn invalid_command(message: &str) -> Value {
+    error("InvalidCommand", vec![("message", message.into())])
+}
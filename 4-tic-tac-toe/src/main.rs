@@ -1,163 +1,1182 @@
-// Exercise 4: A simple 2 player Tic Tac Toe Game
+// Exercise 4: A simple Tic Tac Toe Game for 2 or more players
 
 // This is a excerpt of the excellent tutorial by sunjay at https://github.com/sunjay/tic-tac-toe
 // Please checkout his tutorial for a more in depth explanation of code.
 
 // Declare the module this is a part of
-mod game;
+// The game itself is the tic_tac_toe library (lib.rs), everything here is the program around it
+mod solver;
+mod book;
+mod accessible;
+mod bot;
+mod correspondence;
+mod error;
+mod event_log;
+mod i18n;
+mod input;
+mod machine;
+mod messages;
+mod profiles;
+mod puzzle;
+mod scoreboard;
+mod server;
+mod setup;
+mod spectate;
+mod parallel_solver;
+mod symmetry;
 
 // Standard Library Import Statements
+use std::env;              // Gives access to the command line arguments
+use std::fs;               // Reading and writing whole files
 use std::io::{self, Write}; // Import the "Write" trait
-use std::process;           // Gives access to the exit function
+use std::net::TcpListener;  // Accepts connections for the game server
+use std::path::Path;        // A borrowed file path
+use std::process::ExitCode; // Status reported to the shell when the program ends
+use std::sync::atomic::Ordering; // Setting the flag that stops a solve
+use std::time::Duration;   // How often a solve saves its progress
 
 // Package Import Statements
-use game::{Game, Piece, Winner, Tiles, MoveError};
+use tic_tac_toe::clock::{self, Clocks};
+use tic_tac_toe::game::{Game, Move, Piece, Players, Role, Variant, Winner, Tiles};
+use tic_tac_toe::{animated_svg, game_frames, move_name, parse_move, render_tiles, BoardImage, MoveParser};
+use solver::{Outcome, Solver};
+use book::Book;
+use parallel_solver::{ParallelSolveError, ParallelSolver};
+use bot::Bot;
+use correspondence::Correspondence;
+use error::Error;
+use i18n::{t, tn, Language, LANGUAGES};
+use input::read_line;
+use messages::{describe_tile, describe_tiles};
+use profiles::ProfileStore;
+use scoreboard::Scoreboard;
+use setup::{bot_seat, current_seat, game_builder, is_new_name, new_game, parse_args, seat_count, setup_error, winning_seat, Options};
 
-// Simplify error handling later on
-#[derive(Debug, Clone)]
-pub struct InvalidMove(pub String);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.is_quit() => {
+            eprintln!("{}", err);
+            ExitCode::from(err.exit_code())
+        },
+        Err(err) => {
+            eprintln!("{}", t!("error", error = err));
+            ExitCode::from(err.exit_code())
+        },
+    }
+}
+
+// Runs whatever the command line asks for
+fn run() -> Result<(), Error> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    // The language is picked before anything is printed, so --lang can go anywhere on the command line
+    if let Some(position) = args.iter().position(|arg| arg == "--lang") {
+        let code = args.get(position + 1).ok_or_else(|| t!("args.missing", flag = "--lang"))?;
+        let language = Language::from_code(code).ok_or_else(|| {
+            let codes: Vec<&str> = LANGUAGES.iter().map(|language| language.code()).collect();
+            t!("args.language", language = code, expected = codes.join(", "))
+        })?;
+        i18n::select(language);
+        args.drain(position..position + 2);
+    }
+
+    // Subcommands do something other than play a game
+    match args.first().map(String::as_str) {
+        Some("book") => return build_book(&args[1..]),
+        Some("puzzle") => return run_puzzles(&args[1..]),
+        Some("solve") => return solve(&args[1..]),
+        Some("leaderboard") => return print_leaderboard(),
+        Some("profile") => return print_profile(&args[1..]),
+        Some("serve") => return serve(&args[1..]),
+        Some("new") => return new_correspondence(&args[1..]),
+        Some("move") => return correspondence_move(&args[1..]),
+        Some("show") => return show_correspondence(&args[1..]),
+        Some("export") => return export(&args[1..]),
+        _ => {},
+    }
+
+    // Parse command args
+    let options = parse_args(args.into_iter())?;
+
+    // Spectators watch someone else's game instead of playing one
+    if let Some(target) = &options.spectate {
+        return Ok(spectate::spectate(target)?);
+    }
 
-fn main() {
     // Create the empty Tic Tac Toe Board
-    let mut game = Game::new();
+    let mut game = new_game(&options).map_err(setup_error)?;
+    log_events(&mut game, &options)?;
+
+    // The computer player, also used for hints. Remembers solved positions between moves.
+    let mut bot = match &options.book {
+        Some(path) => Bot::with_book(load_book(Path::new(path), &game)?),
+        None => Bot::new(),
+    };
+    let bot_seat = options.bot.as_ref().map(|bot| bot_seat(&game, bot)).transpose()?;
+
+    // Refuse a computer player up front rather than failing, or making everyone wait, on its first move
+    if bot_seat.is_some() {
+        bot.solver().check(&game).map_err(|err| t!("bot.unsupported", error = err))?;
+    }
+
+    // Scripts drive a single game through JSON, without any of the prompts
+    if options.json {
+        if options.coach || options.best_of.is_some() || options.names.is_some() {
+            return Err(t!("args.json").into());
+        }
+        let clocks = options.time.map(|control| Clocks::for_game(control, &game));
+        return Ok(machine::run(&mut game, &mut bot, bot_seat, clocks)?);
+    }
+
+    // A match plays several games in a row, otherwise there is just the one
+    match options.best_of {
+        Some(best_of) => play_match(&options, best_of, &mut bot, bot_seat)?,
+        None => {
+            // Names only come from --names for a single game, they are needed for profiles
+            let named = options.names.as_ref().map(|_| player_names(&game, &options, bot_seat)).transpose()?;
+            let (names, rated) = named.map_or((None, Vec::new()), |(names, rated)| (Some(names), rated));
+            let seats = Seats {names, bot: bot_seat, rated};
+            let winner = play_game(&mut game, 0, &seats, &options, &mut bot)
+                .map_err(|err| stop_game(err, &game, 0, &seats, &options))?;
+            record_profiles(&seats, winning_seat(winner, 0), &game);
+
+            // Detect Game Over
+            let name = seats.names.as_ref().zip(winning_seat(winner, 0)).map(|(names, seat)| names[seat].as_str());
+            print_result(winner, &game, name);
+        },
+    }
+    Ok(())
+}
+
+// Announces the result of a game, by the winner's name if they have one
+// Accessible output says how the game was won in a full sentence
+fn print_result(winner: Winner, game: &Game, name: Option<&str>) {
+    let who = match (name, winner) {
+        (Some(name), _) => name.to_string(),
+        (None, Winner::Player(piece)) if accessible::is_enabled() => t!("player.symbol", symbol = game.players().symbol(piece).to_uppercase()),
+        (None, Winner::Player(piece)) => game.players().symbol(piece).to_uppercase().to_string(),
+        (None, Winner::Order) => role_name(Role::Order),
+        (None, Winner::Chaos) => role_name(Role::Chaos),
+        (None, Winner::Tie) => String::new(),
+    };
+    if !accessible::is_enabled() {
+        match winner {
+            Winner::Tie => println!("{}", t!("result.tie")),
+            _ => println!("{}", t!("result.wins", who = who)),
+        }
+        return;
+    }
+
+    let full = game.tiles().iter().flatten().all(Option::is_some);
+    let length = game.win_length();
+    let sentence = match (winner, game.winning_line()) {
+        (Winner::Tie, _) => t!("result.tie_full", length = length),
+        (_, Some((from, to))) => t!(
+            "result.wins_line",
+            who = who,
+            length = length,
+            from = describe_tile(from.0, from.1),
+            to = describe_tile(to.0, to.1),
+        ),
+        (_, None) if full => t!("result.wins_full", who = who, length = length),
+        (_, None) => t!("result.wins_time", who = who),
+    };
+    println!("{}", sentence);
+}
+
+// Announces a move in a full sentence for accessible output, e.g. "Alice placed X on row 2, column B."
+fn announce_move(game: &Game, mv: Move, who: &str) {
+    if accessible::is_enabled() {
+        let piece = game.players().symbol(mv.piece).to_uppercase();
+        println!("{}", t!("move.placed", who = who, piece = piece, tile = describe_tile(mv.row, mv.col)));
+    }
+}
+
+// Who sits at the board. Players keep their seat for a whole match: in a standard game
+// the seat is the piece, in Order and Chaos the two seats swap roles every game.
+struct Seats {
+    names: Option<Vec<String>>, // Asked for in a match, or given with --names
+    bot: Option<usize>,         // The seat played by the computer
+    rated: Vec<usize>,          // The seats whose names get a profile: not the computer or a "Player N" stand-in
+}
+
+// Names a seat: the player's name if we know it, otherwise their piece or role
+fn seat_label(game: &Game, seat: usize, game_number: usize, seats: &Seats) -> String {
+    match (&seats.names, game.variant()) {
+        (Some(names), _) => names[seat].clone(),
+        (None, Variant::Standard) => game.players().symbol(Piece::new(seat)).to_string(),
+        (None, Variant::OrderAndChaos) if seat == game_number % 2 => role_name(Role::Order),
+        (None, Variant::OrderAndChaos) => role_name(Role::Chaos),
+    }
+}
+
+// Names a role of Order and Chaos in the player's language
+fn role_name(role: Role) -> String {
+    match role {
+        Role::Order => t!("role.order"),
+        Role::Chaos => t!("role.chaos"),
+    }
+}
+
+// Shows how much time every seat has left, and warns the player to move when they are low
+fn print_clocks(clocks: &Clocks, game: &Game, game_number: usize, seats: &Seats) {
+    let times: Vec<String> = (0..clocks.sides())
+        .map(|seat| format!("{} {}", seat_label(game, seat, game_number, seats), clock::format_time(clocks.remaining(seat))))
+        .collect();
+    println!("{}", t!("clock.left", times = times.join(" | ")));
+
+    if let Some(seat) = clocks.running() {
+        if clocks.remaining(seat) < clock::LOW_TIME {
+            let who = seat_label(game, seat, game_number, seats);
+            println!("{}", tn!("clock.hurry", clock::LOW_TIME.as_secs() as usize, who = who));
+        }
+    }
+}
+
+// Checks the clock of the player to move, taking them out of the game if they have run out of time
+fn out_of_time(clocks: &mut Option<Clocks>, game: &mut Game, game_number: usize, seats: &Seats) -> bool {
+    let seat = current_seat(game, game_number);
+    if !clocks.as_ref().is_some_and(|clocks| clocks.is_flagged(seat)) {
+        return false;
+    }
+
+    println!("{}", t!("clock.flagged", who = seat_label(game, seat, game_number, seats)));
+    game.time_out().expect("the game was still going");
+    true
+}
+
+// Gets a name for every seat from --names, or by asking everyone once, along with the seats to rate
+fn player_names(game: &Game, options: &Options, bot_seat: Option<usize>) -> Result<(Vec<String>, Vec<usize>), Error> {
+    let seat_count = seat_count(game);
+    let rated = |names: &[String]| (0..names.len()).filter(|&seat| Some(seat) != bot_seat).collect();
+
+    if let Some(names) = &options.names {
+        if names.len() != seat_count {
+            return Err(tn!("args.names", seat_count).into());
+        }
+        return Ok((names.clone(), rated(names)));
+    }
+
+    // Typed names go through the same check as --names. Nobody can take the computer's name either.
+    let mut names: Vec<String> = Vec::new();
+    let mut stand_ins = Vec::new();
+    let computer = t!("player.computer");
+    for seat in 0..seat_count {
+        if Some(seat) == bot_seat {
+            names.push(computer.clone());
+            continue;
+        }
+        loop {
+            accessible::prompt(&t!("prompt.name", number = seat + 1))?;
+            let typed = profiles::clean_name(&read_line()?);
+            let name = match typed.is_empty() {
+                true => t!("player.number", number = seat + 1),
+                false => typed.clone(),
+            };
+            if !is_new_name(&names, &name) || (bot_seat.is_some() && name == computer) {
+                eprintln!("{}", t!("prompt.name_taken", name = name));
+                continue;
+            }
+            if typed.is_empty() {
+                stand_ins.push(seat);
+            }
+            names.push(name);
+            break;
+        }
+    }
+    let rated = rated(&names).into_iter().filter(|seat| !stand_ins.contains(seat)).collect();
+    Ok((names, rated))
+}
+
+// Updates the profiles of the rated seats with the result of a finished game
+// A game won by the computer or a stand-in isn't recorded, it says nothing about how the rated players compare
+// Problems with the data file are reported but don't stop the game
+fn record_profiles(seats: &Seats, winner: Option<usize>, game: &Game) {
+    let names = match &seats.names {
+        Some(names) if seats.rated.len() >= 2 => names,
+        _ => return,
+    };
+    let winner = match winner {
+        Some(seat) => match seats.rated.iter().position(|&rated| rated == seat) {
+            Some(index) => Some(index),
+            None => return,
+        },
+        None => None,
+    };
+    let players: Vec<String> = seats.rated.iter().map(|&seat| names[seat].clone()).collect();
+
+    let path = match ProfileStore::default_path() {
+        Some(path) => path,
+        None => return eprintln!("{}", t!("profiles.not_saved")),
+    };
+
+    // Load right before saving, in case another game finished in the meantime
+    let result = ProfileStore::update(&path, |store| store.record_game(&players, winner, game.to_board_string()));
+    if let Err(err) = result {
+        eprintln!("{}", t!("profiles.update_failed", path = path.display(), error = err));
+    }
+}
+
+// Prints every player ranked by rating
+fn print_leaderboard() -> Result<(), Error> {
+    let store = load_profiles()?;
+    let leaderboard = store.leaderboard();
+    if leaderboard.is_empty() {
+        println!("{}", t!("leaderboard.empty"));
+        return Ok(());
+    }
+
+    // Every column is as wide as its heading or its widest value, whichever is wider
+    let headings = [
+        t!("leaderboard.rank"),
+        t!("leaderboard.name"),
+        t!("leaderboard.rating"),
+        t!("leaderboard.wins"),
+        t!("leaderboard.losses"),
+        t!("leaderboard.ties"),
+    ];
+    let rows: Vec<[String; 6]> = leaderboard.iter().enumerate().map(|(rank, profile)| [
+        (rank + 1).to_string(),
+        profile.name.clone(),
+        format!("{:.0}", profile.rating),
+        profile.wins.to_string(),
+        profile.losses.to_string(),
+        profile.ties.to_string(),
+    ]).collect();
+    let widths: Vec<usize> = (0..headings.len())
+        .map(|column| rows.iter().chain([&headings]).map(|row| row[column].chars().count()).max().unwrap_or(0))
+        .collect();
+
+    // Names line up on the left, numbers on the right
+    let line = |row: &[String; 6]| {
+        let cells: Vec<String> = row.iter().zip(&widths).enumerate().map(|(column, (cell, &width))| match column {
+            1 => format!("{:<width$}", cell, width = width),
+            _ => format!("{:>width$}", cell, width = width),
+        }).collect();
+        println!("{}", cells.join("  "));
+    };
+    line(&headings);
+    rows.iter().for_each(line);
+    Ok(())
+}
+
+// Prints one player's rating, record and latest games
+fn print_profile(args: &[String]) -> Result<(), Error> {
+    let name = match args {
+        [name] => name,
+        _ => return Err(t!("profile.usage").into()),
+    };
+    let store = load_profiles()?;
+    let profile = store.profile(name).ok_or_else(|| t!("profile.unknown", name = name))?;
+
+    println!("{}", profile.name);
+    println!("{}", t!("profile.rating", rating = format!("{:.0}", profile.rating)));
+    println!("{}", t!(
+        "profile.record",
+        wins = tn!("count.wins", profile.wins),
+        losses = tn!("count.losses", profile.losses),
+        ties = tn!("count.ties", profile.ties),
+    ));
+
+    // Newest games first
+    println!("{}", t!("profile.recent"));
+    for id in profile.games.iter().rev().take(10) {
+        if let Some(record) = store.game(id) {
+            let opponents: Vec<&str> = record.players.iter().map(String::as_str).filter(|&player| player != name).collect();
+            let (id, opponents, board) = (&record.id, opponents.join(", "), &record.board);
+            let line = match &record.winner {
+                Some(winner) if winner == name => t!("profile.won", id = id, opponents = opponents, board = board),
+                Some(_) => t!("profile.lost", id = id, opponents = opponents, board = board),
+                None => t!("profile.tied", id = id, opponents = opponents, board = board),
+            };
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
+
+// Hosts games over HTTP until stopped: serve [--addr host:port]
+fn serve(args: &[String]) -> Result<(), Error> {
+    let addr = match args {
+        [] => "127.0.0.1:8080",
+        [flag, addr] if flag == "--addr" => addr.as_str(),
+        _ => return Err(t!("serve.usage").into()),
+    };
+    let listen_error = |err| t!("serve.listen_failed", address = addr, error = err);
+    let listener = TcpListener::bind(addr).map_err(listen_error)?;
+
+    // Port 0 picks a free port, so print the one we actually got
+    let local_addr = listener.local_addr().map_err(listen_error)?;
+    println!("{}", t!("serve.listening", address = local_addr));
+    io::stdout().flush()?;
+
+    server::serve(listener).map_err(|err| t!("serve.stopped", error = err))?;
+    Ok(())
+}
+
+// Starts a correspondence game in a file: new <file> --names Alice,Bob [game options]
+fn new_correspondence(args: &[String]) -> Result<(), Error> {
+    let SubcommandArgs {positional, options, ..} = split_args(args, &[])?;
+    let path = match &positional[..] {
+        [path] => Path::new(path),
+        _ => return Err(t!("correspondence.new_usage").into()),
+    };
+    if options.coach || options.json || options.bot.is_some() || options.book.is_some()
+        || options.best_of.is_some() || options.time.is_some() || options.spectate.is_some()
+    {
+        return Err(t!("correspondence.options").into());
+    }
+
+    // Every seat needs its own name, since moves are signed with it
+    let game = new_game(&options).map_err(setup_error)?;
+    let names = options.names.clone().ok_or_else(|| t!("correspondence.names"))?;
+    if names.len() != seat_count(&game) {
+        return Err(tn!("args.names", seat_count(&game)).into());
+    }
+
+    let _lock = correspondence::lock(path).map_err(|err| t!("file.lock_failed", path = path.display(), error = err))?;
+    if path.exists() {
+        return Err(t!("file.exists", path = path.display()).into());
+    }
+    let record = Correspondence::new(&options, names);
+    record.save(path).map_err(|err| t!("file.write_failed", path = path.display(), error = err))?;
+    print_correspondence(&record, &game);
+    Ok(())
+}
+
+// Plays your move in a correspondence game: move <file> <move> [--as name]
+// Without --as, the name comes from $TTT_PLAYER or $USER
+fn correspondence_move(args: &[String]) -> Result<(), Error> {
+    let SubcommandArgs {positional, flags, ..} = split_args(args, &["--as"])?;
+    let (path, input) = match &positional[..] {
+        [path, mv @ ..] if !mv.is_empty() => (Path::new(path), mv.join(" ")),
+        _ => return Err(t!("correspondence.move_usage").into()),
+    };
+    let player = flags.into_iter().map(|(_, name)| name).next_back()
+        .or_else(|| env::var("TTT_PLAYER").ok())
+        .or_else(|| env::var("USER").ok())
+        .map(|name| profiles::clean_name(&name))
+        .ok_or_else(|| t!("correspondence.who"))?;
+
+    // Nobody else can change the file between reading it and writing the move back
+    let _lock = correspondence::lock(path).map_err(|err| t!("file.lock_failed", path = path.display(), error = err))?;
+    let mut record = Correspondence::load(path)?;
+    let game = record.play(&player, &input)?;
+    record.save(path).map_err(|err| t!("file.write_failed", path = path.display(), error = err))?;
+    print_correspondence(&record, &game);
+    Ok(())
+}
+
+// Prints a correspondence game and every signed move in it: show <file>
+fn show_correspondence(args: &[String]) -> Result<(), Error> {
+    let path = match args {
+        [path] => Path::new(path),
+        _ => return Err(t!("correspondence.show_usage").into()),
+    };
+    let record = Correspondence::load(path)?;
+    let game = record.game()?;
+
+    for (number, mv) in record.moves().iter().enumerate() {
+        println!("{:>3}. {} {}", number + 1, mv.player, mv.mv);
+    }
+    if !record.moves().is_empty() {
+        println!();
+    }
+    print_correspondence(&record, &game);
+    Ok(())
+}
+
+// Draws a game as pictures: export [game file] [--format svg|png] [--out file] [--moves 2B,1A] [game options]
+// The game is a correspondence game file, or is set up from the game options, then --moves are played.
+//   --numbers       writes the move number on every piece played
+//   --animate       one SVG that plays through the game
+//   --frames        a picture per position, numbered after --out (game.svg becomes game-0.svg, game-1.svg, ...)
+//   --tile-size 80  pixels per tile in PNGs (SVGs scale to any size)
+fn export(args: &[String]) -> Result<(), Error> {
+
+    // Switches are picked out first, since the subcommand's own flags all take a value
+    let switches = ["--numbers", "--animate", "--frames"];
+    let has = |switch: &str| args.iter().any(|arg| arg == switch);
+    let (numbers, animate, frames) = (has("--numbers"), has("--animate"), has("--frames"));
+    let args: Vec<String> = args.iter().filter(|arg| !switches.contains(&arg.as_str())).cloned().collect();
+    let SubcommandArgs {positional, flags, options} = split_args(&args, &["--format", "--out", "--moves", "--tile-size"])?;
+
+    let mut png = false;
+    let mut out = None;
+    let mut moves = None;
+    let mut tile_size = None;
+    for (flag, value) in flags {
+        match flag.as_str() {
+            "--format" => png = match value.as_str() {
+                "svg" => false,
+                "png" => true,
+                _ => return Err(t!("export.format", format = value).into()),
+            },
+            "--out" => out = Some(value),
+            "--moves" => moves = Some(value),
+            _ => tile_size = Some(parse_number(&flag, &value)? as u32),
+        }
+    }
+    if animate && (png || frames) {
+        return Err(t!("export.animate").into());
+    }
+
+    let mut game = match &positional[..] {
+        [] => new_game(&options).map_err(setup_error)?,
+        [path] => Correspondence::load(Path::new(path))?.game()?,
+        _ => return Err(t!("export.usage").into()),
+    };
+    for input in moves.iter().flat_map(|moves| moves.split(',')) {
+        let (row, col, piece) = parse_move(input, game.tiles().len(), game.players())?;
+        let result = match piece {
+            Some(piece) => game.make_move_with(row, col, piece),
+            None => game.make_move(row, col),
+        };
+        result.map_err(|err| messages::move_error(&err, game.players()))?;
+    }
+
+    // Sets up and draws one picture
+    let draw = |image: BoardImage, history: &[Move]| {
+        let image = if numbers { image.move_numbers(history) } else { image };
+        let image = match tile_size {
+            Some(pixels) => image.tile_size(pixels),
+            None => image,
+        };
+        if png { image.to_png() } else { image.to_svg().into_bytes() }
+    };
+    let write = |path: Option<&Path>, contents: &[u8]| -> Result<(), Error> {
+        match path {
+            Some(path) => fs::write(path, contents).map_err(|err| t!("file.write_failed", path = path.display(), error = err).into()),
+            None => Ok(io::stdout().write_all(contents)?),
+        }
+    };
+
+    if animate {
+        return write(out.as_deref().map(Path::new), animated_svg(&game, 1.0, numbers).as_bytes());
+    }
+    if !frames {
+        return write(out.as_deref().map(Path::new), &draw(BoardImage::from_game(&game), game.history()));
+    }
+
+    // Frames need a name to number
+    let out = Path::new(out.as_deref().ok_or_else(|| t!("export.frames"))?);
+    let stem = out.file_stem().map_or("frame".into(), |stem| stem.to_string_lossy());
+    let extension = if png { "png" } else { "svg" };
+    for (number, frame) in game_frames(&game).into_iter().enumerate() {
+        let path = out.with_file_name(format!("{}-{}.{}", stem, number, extension));
+        write(Some(&path), &draw(frame, &game.history()[..number]))?;
+    }
+    println!("{}", tn!("export.wrote", game.history().len() + 1, path = out.display()));
+    Ok(())
+}
+
+// Prints the board of a correspondence game and who has to move next
+fn print_correspondence(record: &Correspondence, game: &Game) {
+    print_tiles(game.tiles(), game.players());
+    let names = record.names();
+
+    match (game.winner(), game.current_role()) {
+        (Some(winner), _) => print_result(winner, game, winning_seat(winner, 0).map(|seat| names[seat].as_str())),
+        (None, Some(Role::Order)) => println!("{}", t!("correspondence.order", name = names[current_seat(game, 0)])),
+        (None, Some(Role::Chaos)) => println!("{}", t!("correspondence.chaos", name = names[current_seat(game, 0)])),
+        (None, None) => {
+            let piece = game.players().symbol(game.current_piece());
+            println!("{}", t!("correspondence.piece", name = names[current_seat(game, 0)], piece = piece));
+        },
+    }
+}
+
+// Loads the profiles for the subcommands
+fn load_profiles() -> Result<ProfileStore, Error> {
+    let path = ProfileStore::default_path().ok_or_else(|| t!("profiles.no_directory"))?;
+    Ok(ProfileStore::load(&path).map_err(|err| t!("file.read_failed", path = path.display(), error = err))?)
+}
+
+// Plays a best-of-N match, swapping who starts each game, and prints the final scores
+fn play_match(options: &Options, best_of: usize, bot: &mut Bot, bot_seat: Option<usize>) -> Result<(), Error> {
+    let first_game = new_game(options).map_err(setup_error)?;
+    let players = first_game.players().clone();
+    let (names, rated) = player_names(&first_game, options, bot_seat)?;
+
+    // Standard games label each name with the player's piece
+    let labels = names.iter().enumerate().map(|(seat, name)| match first_game.variant() {
+        Variant::Standard => format!("{} ({})", name, players.symbol(Piece::new(seat))),
+        Variant::OrderAndChaos => name.clone(),
+    }).collect();
+    let mut scoreboard = Scoreboard::new(labels, best_of);
+    let seats = Seats {names: Some(names.clone()), bot: bot_seat, rated};
+
+    // Whoever moves first this game is the next one along in the turn order
+    let turn_order = players.turn_order();
+    let first = turn_order.iter().position(|&piece| piece == first_game.current_piece()).unwrap_or(0);
+
+    let mut game_number = 0;
+    while !scoreboard.is_decided() {
+        println!("{}", t!("match.game", number = game_number + 1, total = best_of));
+        let mut game = match first_game.variant() {
+            Variant::Standard => game_builder(options)
+                .and_then(|builder| {
+                    let starting_piece = turn_order[(first + game_number) % turn_order.len()];
+                    builder.starting_piece(starting_piece).build().map_err(|err| messages::setup_error(&err))
+                })
+                .map_err(setup_error)?,
+            Variant::OrderAndChaos => first_game.clone(),
+        };
+        log_events(&mut game, options)?;
+
+        let winner = play_game(&mut game, game_number, &seats, options, bot).map_err(|err| {
+            let err = stop_game(err, &game, game_number, &seats, options);
+            if err.is_quit() {
+                println!("{}", t!("match.score", score = scoreboard.summary()));
+            }
+            err
+        })?;
+        let seat = winning_seat(winner, game_number);
+        record_profiles(&seats, seat, &game);
+        print_result(winner, &game, seat.map(|seat| names[seat].as_str()));
+        scoreboard.record(seat);
+        println!("{}\n", t!("match.score", score = scoreboard.summary()));
+        game_number += 1;
+    }
+
+    // Final results
+    println!("{}", scoreboard);
+    match scoreboard.leader() {
+        Some(seat) => println!("{}", t!("match.wins", name = names[seat])),
+        None => println!("{}", t!("match.draw")),
+    }
+    Ok(())
+}
+
+// Plays a game until it is finished and returns the winner
+fn play_game(game: &mut Game, game_number: usize, seats: &Seats, options: &Options, bot: &mut Bot) -> Result<Winner, Error> {
+    // Every seat gets a clock if the game is timed
+    let mut clocks = options.time.map(|control| Clocks::new(control, seat_count(game)));
 
     // Main loop for the game
     while !game.is_finished() {
-        print_tiles(game.tiles());
+        let seat = current_seat(game, game_number);
+
+        // Start the clock of the player to move and show everyone's time above the board
+        if let Some(clocks) = &mut clocks {
+            if clocks.running() != Some(seat) {
+                clocks.start(seat);
+            }
+            print_clocks(clocks, game, game_number, seats);
+        }
+        print_tiles(game.tiles(), game.players());
+
+        // Prints the current player, piece or role (Who's turn is it?)
+        let role = game.current_role().map(role_name);
+        let piece = game.players().symbol(game.current_piece());
+        let turn = match (&seats.names, &role) {
+            (Some(names), Some(role)) => t!("turn.player_role", name = names[seat], role = role),
+            (Some(names), None) => t!("turn.player_piece", name = names[seat], piece = piece),
+            (None, Some(role)) => t!("turn.role", role = role),
+            (None, None) => t!("turn.piece", piece = piece),
+        };
+        println!("{}", turn);
 
-        // Prints the current piece (Who's turn is it?)
-        println!("Current piece: {}", match game.current_piece() {
-            Piece::X => "x",
-            Piece::O => "o",
-        });
+        // Let the computer move if it's playing this side
+        if seats.bot == Some(seat) {
+            match bot.choose_move(game) {
+                Ok(_) if out_of_time(&mut clocks, game, game_number, seats) => continue,
+                Ok((mv, _)) => {
+                    match accessible::is_enabled() {
+                        true => announce_move(game, mv, &t!("player.the_computer")),
+                        false => println!("{}", t!("move.computer", move_name = move_name(game, mv))),
+                    }
+                    game.play(mv).expect("the bot only picks legal moves");
+                    if let Some(clocks) = clocks.as_mut() {
+                        clocks.stop();
+                    }
+                },
+                Err(err) => return Err(t!("move.computer_failed", error = err).into()),
+            }
+            continue;
+        }
 
         // Collect the player's intended move
-        let (row, col) = prompt_move();
-
-        // Detect if the move is valid
-        // unreachable!() exits the program with an error message
-        match game.make_move(row, col) {
-            Ok(()) => {},
-            Err(MoveError::GameAlreadyOver) => unreachable!("Game was already over when it should not have been"),
-            Err(MoveError::InvalidPosition {row, col}) => {
-                unreachable!("Should not be able to enter an invalid move, but still got ({}, {})", row, col)
+        let (row, col, piece) = prompt_move(game, bot, MoveParser::new().numpad(options.numpad))?;
+
+        // Give the player a chance to take back a move that makes things worse
+        if options.coach && !coach_move(game, bot.solver(), row, col, piece)? {
+            continue;
+        }
+
+        // A move made after the clock ran out doesn't count
+        if out_of_time(&mut clocks, game, game_number, seats) {
+            continue;
+        }
+
+        // Detect if the move is valid, explaining the problem to the player if it isn't
+        let result = match piece {
+            Some(piece) => game.make_move_with(row, col, piece),
+            None => game.make_move(row, col),
+        };
+        match result {
+            Ok(()) => {
+                if let Some(clocks) = clocks.as_mut() {
+                    clocks.stop();
+                }
+                let mv = *game.history().last().expect("a move was just played");
+                let who = match (&seats.names, role) {
+                    (Some(names), _) => names[seat].clone(),
+                    (None, Some(role)) => role,
+                    (None, None) => t!("player.symbol", symbol = game.players().symbol(mv.piece).to_uppercase()),
+                };
+                announce_move(game, mv, &who);
             },
-            Err(MoveError::TileNotEmpty {other_piece, row, col}) => eprintln!(
-                "The tile at position {}{} already has piece {} in it!",
-                row + 1,
-                (b'A' + col as u8) as char, // Get the char `col` away from byte literal 'A' (i.e. A, B, or C)
-                match other_piece {
-                    Piece::X => "x",
-                    Piece::O => "o",
-                },
-            ),
+            Err(err) => eprintln!("{}", messages::move_error(&err, game.players())),
         }
     }
 
     // Refresh the game board
-    print_tiles(game.tiles());
+    print_tiles(game.tiles(), game.players());
+    Ok(game.winner().expect("finished game should have winner"))
+}
 
-    // Detect Game Over
-    match game.winner().expect("finished game should have winner") {
-        Winner::X => println!("X wins!"),
-        Winner::O => println!("O wins!"),
-        Winner::Tie => println!("Tie!"),
+// Sums up a game the player quit before it finished, and after Ctrl-C offers to save it as a
+// correspondence game so it can be finished later. Other errors are passed along untouched.
+fn stop_game(err: Error, game: &Game, game_number: usize, seats: &Seats, options: &Options) -> Error {
+    if !err.is_quit() {
+        return err;
     }
+    println!();
+    print_tiles(game.tiles(), game.players());
+    println!("{}", tn!("stop.moves", game.history().len()));
+
+    // There is nobody left to ask once the input has ended
+    if let Error::Interrupted = err {
+        let _ = accessible::prompt(&t!("stop.save"));
+        match read_line() {
+            Ok(path) if !path.trim().is_empty() => match save_game(Path::new(path.trim()), game, game_number, seats, options) {
+                Ok(()) => println!("{}", t!("stop.saved", path = path.trim())),
+                Err(save_err) => eprintln!("{}", t!("stop.save_failed", error = save_err)),
+            },
+            _ => println!("{}", t!("stop.not_saved")),
+        }
+    }
+    err
 }
 
-// Prompts and attempt to collect the player's intended move
-fn prompt_move() -> (usize, usize) {
-    // Loops until the player enters a valid move
+// Saves a game in progress as a correspondence game, with the players' names (or "Player 1", ...) as the seats
+fn save_game(path: &Path, game: &Game, game_number: usize, seats: &Seats, options: &Options) -> Result<(), Error> {
+    let mut names = match &seats.names {
+        Some(names) => names.clone(),
+        None => (0..seat_count(game))
+            .map(|seat| if seats.bot == Some(seat) { t!("player.computer") } else { t!("player.number", number = seat + 1) })
+            .collect(),
+    };
+
+    // Correspondence games are always a match's first game: Order is the first seat and the piece
+    // that moved first moves first again
+    let mut options = options.clone();
+    match game.variant() {
+        Variant::Standard => {
+            let first = game.history().first().map_or(game.current_piece(), |mv| mv.piece);
+            options.first = Some(game.players().symbol(first));
+        },
+        Variant::OrderAndChaos => names.rotate_left(game_number % 2),
+    }
+
+    let _lock = correspondence::lock(path)?;
+    if path.exists() {
+        return Err(t!("file.exists", path = path.display()).into());
+    }
+    let record = Correspondence::resume(&options, names, game)?;
+    record.save(path)?;
+    Ok(())
+}
+
+// Starts the event log given with --log, if there is one
+fn log_events(game: &mut Game, options: &Options) -> Result<(), Error> {
+    if let Some(path) = &options.log {
+        event_log::attach(game, Path::new(path)).map_err(|err| t!("log.failed", path = path, error = err))?;
+    }
+    Ok(())
+}
+
+// A subcommand's arguments, separated from the game options after them
+struct SubcommandArgs {
+    positional: Vec<String>,
+    flags: Vec<(String, String)>, // The subcommand's own flags and their values
+    options: Options,
+}
+
+// Separates a subcommand's own flags (which all take a value) from the game options
+fn split_args(args: &[String], own_flags: &[&str]) -> Result<SubcommandArgs, String> {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+    let mut game_args: Vec<String> = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if own_flags.contains(&arg.as_str()) {
+            let value = args.next().ok_or_else(|| t!("args.missing", flag = arg))?;
+            flags.push((arg.clone(), value.clone()));
+        }
+        // Values of game options stay with their flag
+        else if arg.starts_with("--")
+            || game_args.last().is_some_and(|flag| flag.starts_with("--") && !is_switch(flag))
+        {
+            game_args.push(arg.clone());
+        }
+        else {
+            positional.push(arg.clone());
+        }
+    }
+
+    let options = parse_args(game_args.into_iter())?;
+    Ok(SubcommandArgs {positional, flags, options})
+}
+
+// Checks if a game option is a switch that doesn't take a value
+fn is_switch(flag: &str) -> bool {
+    flag == "--order-chaos" || flag == "--coach" || flag == "--numpad" || flag == "--accessible"
+}
+
+// Parses the number given to a subcommand flag
+fn parse_number(flag: &str, value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| t!("args.number", flag = flag, value = value))
+}
+
+// Builds an opening book from the command line: book <file> [--depth N] [game options]
+fn build_book(args: &[String]) -> Result<(), Error> {
+    let SubcommandArgs {positional, flags, options} = split_args(args, &["--depth"])?;
+    let path = match &positional[..] {
+        [path] => path,
+        _ => return Err(t!("book.usage").into()),
+    };
+    let depth = match flags.last() {
+        Some((flag, value)) => parse_number(flag, value)?,
+        None => 9,
+    };
+
+    // Solve every position up to the depth and save them
+    let game = new_game(&options).map_err(setup_error)?;
+    let book = Book::build(&game, depth, &mut Solver::new())
+        .map_err(|err| t!("book.build_failed", error = err))?;
+    book.write_to(Path::new(path))
+        .map_err(|err| t!("book.write_failed", error = err))?;
+    println!("{}", tn!("book.wrote", book.len(), depth = book.depth(), path = path));
+    Ok(())
+}
+
+// Finds "win in N" puzzles and plays them, or saves them:
+// puzzle [--moves N] [--count N] [--export file] [game options]
+fn run_puzzles(args: &[String]) -> Result<(), Error> {
+    let SubcommandArgs {positional, flags, options} = split_args(args, &["--moves", "--count", "--export"])?;
+    if !positional.is_empty() {
+        return Err(t!("puzzle.usage").into());
+    }
+    let mut moves = 2;
+    let mut count = 5;
+    let mut export = None;
+    for (flag, value) in flags {
+        match flag.as_str() {
+            "--moves" => moves = parse_number(&flag, &value)?.max(1),
+            "--count" => count = parse_number(&flag, &value)?,
+            _ => export = Some(value),
+        }
+    }
+
+    // Search every position reachable from the start for puzzles
+    let game = new_game(&options).map_err(setup_error)?;
+    let mut solver = Solver::new();
+    let puzzles = puzzle::generate(&game, moves, &mut solver)
+        .map_err(|err| t!("puzzle.generate_failed", error = err))?;
+
+    // Save every puzzle, one per line: board | win in N | difficulty D (L lines) | solution
+    if let Some(path) = export {
+        let lines: Vec<String> = puzzles.iter().map(|puzzle| {
+            let solution: Vec<String> = puzzle.solution.iter().map(|&mv| move_name(&puzzle.game, mv)).collect();
+            format!(
+                "{} | win in {} | difficulty {} ({} {}) | solution {}\n",
+                puzzle.game.to_board_string(),
+                puzzle.moves,
+                puzzle.difficulty,
+                puzzle.lines,
+                if puzzle.lines == 1 { "line" } else { "lines" },
+                solution.join(" "),
+            )
+        }).collect();
+        fs::write(&path, lines.concat()).map_err(|err| t!("file.write_failed", path = path, error = err))?;
+        println!("{}", tn!("puzzle.wrote", puzzles.len(), path = path));
+        return Ok(());
+    }
+
+    // Otherwise play through them
+    let total = puzzles.len().min(count);
+    let mut solved = 0;
+    let mut bot = Bot::new();
+    for (i, puzzle) in puzzles.iter().take(count).enumerate() {
+        println!("{}", t!(
+            "puzzle.start",
+            number = i + 1,
+            total = total,
+            piece = game.players().symbol(puzzle.game.current_piece()),
+            moves = tn!("count.moves", puzzle.moves),
+            difficulty = puzzle.difficulty,
+            max = puzzle::MAX_DIFFICULTY,
+        ));
+        match play_puzzle(puzzle, &mut bot) {
+            Ok(true) => solved += 1,
+            Ok(false) => {},
+            Err(err) => {
+                if err.is_quit() {
+                    println!("\n{}", tn!("puzzle.score", i, solved = solved));
+                }
+                return Err(err);
+            },
+        }
+    }
+    println!("{}", tn!("puzzle.score", total, solved = solved));
+    Ok(())
+}
+
+// Plays one puzzle, with the computer defending. Returns whether it was solved.
+fn play_puzzle(puzzle: &puzzle::Puzzle, bot: &mut Bot) -> Result<bool, Error> {
+    let mut game = puzzle.game.clone();
+    let mut plies_left = puzzle.moves * 2 - 1;
+
     loop {
-        // Print a prompt. Flush is necessary due to line-buffering not printing
-        // unless it detects a new line character or a flush.
-        print!("Enter move (e.g. 1A): ");
-        io::stdout().flush().expect("Failed to flush stdout");
+        print_tiles(game.tiles(), game.players());
+        let (row, col, piece) = prompt_move(&game, bot, MoveParser::new())?;
+        let mv = Move {row, col, piece: piece.unwrap_or_else(|| game.current_piece())};
 
-        // Collect keyboard input
-        let line = read_line();
+        // Any move that still wins in time counts, not just the one in the solution
+        let check_failed = |err| t!("puzzle.check_failed", error = err);
+        if !puzzle::is_winning_answer(&game, mv, plies_left, bot.solver()).map_err(check_failed)? {
+            // Past the first move the solution line may no longer apply, so show a move that wins from here
+            let (winning, _) = bot.solver().best_move(&game).map_err(check_failed)?;
+            println!("{}\n", t!("puzzle.wrong", move_name = move_name(&game, winning)));
+            return Ok(false);
+        }
+        announce_move(&game, mv, &t!("player.you"));
+        game.play(mv).expect("winning answers are legal moves");
+        if game.is_finished() {
+            print_tiles(game.tiles(), game.players());
+            println!("{}\n", t!("puzzle.solved"));
+            return Ok(true);
+        }
 
-        // Matching the output of parse_move() to see if we need to prompt again
-        match parse_move(&line) {
-            Ok((row, col)) => break (row, col),
-            Err(InvalidMove(invalid_str)) => eprintln!(
-                "Invalid move: '{}'. Please try again.",
-                invalid_str,
-            ),
+        // The computer puts up the longest defence
+        let (reply, _) = bot.choose_move(&game).expect("the puzzle is not over yet");
+        match accessible::is_enabled() {
+            true => announce_move(&game, reply, &t!("player.the_computer")),
+            false => println!("{}", t!("move.computer", move_name = move_name(&game, reply))),
         }
+        game.play(reply).expect("the bot only picks legal moves");
+        plies_left -= 2;
     }
 }
 
-// Parses the input for correct formatting and valid moves
-fn parse_move(input: &str) -> Result<(usize, usize), InvalidMove> {
-    // Input should consist of exactly one row and one col
-    if input.len() != 2 {
-        return Err(InvalidMove(input.to_string()));
+// Solves a whole game on several threads and reports its value:
+// solve [--threads N] [--checkpoint file] [--every seconds] [game options]
+// Without --size and --win it solves 4x4 with four in a row. A bigger board needs as many in a row as
+// it is wide unless --win says otherwise.
+fn solve(args: &[String]) -> Result<(), Error> {
+    let SubcommandArgs {positional, flags, mut options} = split_args(args, &["--threads", "--checkpoint", "--every"])?;
+    if !positional.is_empty() {
+        return Err(t!("solve.usage").into());
+    }
+    let mut solver = ParallelSolver::new();
+    let mut checkpoint = None;
+    let mut every = 60;
+    for (flag, value) in flags {
+        match flag.as_str() {
+            "--threads" => solver = solver.threads(parse_number(&flag, &value)?),
+            "--every" => every = parse_number(&flag, &value)?.max(1),
+            _ => checkpoint = Some(value),
+        }
     }
+    let size = *options.size.get_or_insert(4);
+    options.win_length.get_or_insert(size);
+    let game = new_game(&options).map_err(setup_error)?;
 
-    // Valid rows are 1-3
-    let row = match &input[0..1] {
-        "1" => 0,
-        "2" => 1,
-        "3" => 2,
-        _   => return Err(InvalidMove(input.to_string())), 
-    };
+    // Progress goes to stderr, so the report on stdout stays the same from one run to the next
+    if let Some(path) = &checkpoint {
+        solver = solver.checkpoint(Path::new(path), Duration::from_secs(every as u64));
+    }
+    let checkpoint_path = checkpoint.clone();
+    solver = solver.on_progress(move |progress| {
+        let line = t!("solve.progress", nodes = progress.nodes, positions = progress.positions, seconds = progress.elapsed.as_secs());
+        match (progress.checkpointed, &checkpoint_path) {
+            (true, Some(path)) => eprintln!("{} {}", line, t!("solve.progress_saved", path = path)),
+            _ => eprintln!("{}", line),
+        }
+    });
 
-    // Valid rows are A-C (ignoring case)
-    let col = match &input[1..2] {
-        "A" | "a" => 0,
-        "B" | "b" => 1,
-        "C" | "c" => 2,
+    // Ctrl-C saves the progress, if there is a checkpoint, before stopping
+    let stop = solver.stop_flag();
+    if let Err(err) = ctrlc::set_handler(move || stop.store(true, Ordering::Relaxed)) {
+        eprintln!("{}", t!("solve.no_interrupt", error = err));
+    }
 
-        _ => return Err(InvalidMove(input.to_string())),
+    let piece = game.players().symbol(game.current_piece());
+    let size = game.tiles().len();
+    println!("{}", tn!("solve.start", solver.thread_count(), size = size, length = game.win_length(), piece = piece));
+    let report = match solver.solve(&game) {
+        Ok(report) => report,
+        Err(ParallelSolveError::Interrupted) => {
+            if let Some(path) = &checkpoint {
+                eprintln!("{}", t!("solve.interrupted", path = path));
+            }
+            return Err(Error::Interrupted);
+        },
+        Err(ParallelSolveError::Unsupported) => return Err(t!("solve.unsupported").into()),
+        Err(ParallelSolveError::Io(err)) => {
+            return Err(t!("solve.checkpoint_failed", path = checkpoint.unwrap_or_default(), error = err).into());
+        },
+        Err(ParallelSolveError::BadCheckpoint {reason}) => {
+            return Err(t!("solve.checkpoint_failed", path = checkpoint.unwrap_or_default(), error = reason).into());
+        },
+        Err(ParallelSolveError::Solve(err)) => return Err(t!("solve.failed", error = err).into()),
     };
 
-    // If all checks pass, return a (row, col) tuple
-    Ok((row, col))
+    if report.resumed > 0 {
+        println!("{}", tn!("solve.resumed", report.resumed));
+    }
+    println!("{}", t!("solve.value", piece = piece, outcome = outcome_name(report.outcome)));
+    println!("{}", t!("solve.best", move_name = move_name(&game, report.best_move)));
+    println!("{}", t!("solve.nodes", nodes = report.nodes, hits = report.table_hits));
+    println!("{}", t!("solve.positions", positions = report.positions));
+    let seconds = report.elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 { report.nodes as f64 / seconds } else { 0.0 };
+    println!("{}", tn!(
+        "solve.time",
+        report.threads,
+        seconds = format!("{:.2}", seconds),
+        rate = format!("{:.0}", rate),
+    ));
+    Ok(())
 }
 
-// Wrapper function for io::stdin().read_line()
-fn read_line() -> String {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).expect("Failed to read input");
+// Loads an opening book, making sure it was built for this game's rules
+fn load_book(path: &Path, game: &Game) -> Result<Book, Error> {
+    let book = Book::read_from(path).and_then(|book| book.check_rules(game).map(|()| book));
+    Ok(book.map_err(|err| t!("book.unusable", path = path.display(), error = err))?)
+}
 
-    // Exit program if the input is empty
-    if input.is_empty() {
-        println!();
-        process::exit(0);
+// Prompts and attempt to collect the player's intended move
+// Typing "hint" or "?" instead shows the best move
+fn prompt_move(game: &Game, bot: &mut Bot, parser: MoveParser) -> Result<(usize, usize, Option<Piece>), Error> {
+    // Order and Chaos moves also name the piece to place
+    let example = match game.variant() {
+        Variant::Standard if parser.is_numpad() && game.tiles().len() == 3 => "7",
+        Variant::Standard => "1A",
+        Variant::OrderAndChaos => "3D o",
+    };
+
+    // Loops until the player enters a valid move
+    loop {
+        // Print a prompt. Accessible prompts also say how to hear which tiles are free.
+        match accessible::is_enabled() {
+            true => accessible::prompt(&t!("prompt.move_accessible", example = example, moves = t!("command.moves")))?,
+            false => accessible::prompt(&t!("prompt.move", example = example))?,
+        }
+
+        // Collect keyboard input
+        let line = read_line()?;
+
+        // Ask the solver for help instead of moving. The English commands work in every language.
+        if line == "?" || is_answer(&line, "hint", &t!("command.hint")) {
+            print_hint(game, bot);
+            continue;
+        }
+        if is_answer(&line, "moves", &t!("command.moves")) {
+            print_legal_moves(game);
+            continue;
+        }
+
+        // Matching the output of parse() to see if we need to prompt again
+        match parser.parse(&line, game.tiles().len(), game.players()) {
+            Ok(parsed) => break Ok(parsed),
+            Err(err) => eprintln!("{}", t!("prompt.retry", error = messages::invalid_move(&err))),
+        }
     }
+}
 
-    // Trim input
-    let len_without_newline = input.trim_end().len();
-    input.truncate(len_without_newline);
-    input
+// Prints the best move for the current player and where it leads
+fn print_hint(game: &Game, bot: &mut Bot) {
+    match bot.choose_move(game) {
+        Ok((mv, outcome)) => println!("{}", t!("hint.best", move_name = move_name(game, mv), outcome = outcome_name(outcome))),
+        Err(err) => eprintln!("{}", t!("hint.none", error = err)),
+    }
 }
 
-// Print the game board
-fn print_tiles(tiles: &Tiles) {
-    print!("  ");
+// Lists every tile a move can be played on, in words for accessible output
+fn print_legal_moves(game: &Game) {
+    let mut tiles: Vec<(usize, usize)> = game.legal_moves().iter().map(|mv| (mv.row, mv.col)).collect();
+    tiles.dedup(); // Order and Chaos has two moves per tile, one for each piece
 
-    // Print col headers
-    for j in 0..tiles[0].len() as u8 {
-        print!(" {}", (b'A' + j) as char);
+    if accessible::is_enabled() {
+        let tiles: Vec<String> = tiles.iter().map(|&(row, col)| describe_tile(row, col)).collect();
+        println!("{}", tn!("moves.free_accessible", tiles.len(), tiles = tiles.join("; ")));
+    } else {
+        let tiles: Vec<String> = tiles.iter().map(|&(row, col)| tic_tac_toe::tile_name(row, col)).collect();
+        println!("{}", t!("moves.free", tiles = tiles.join(" ")));
     }
-    println!(); // Spacing
+}
+
+// Warns when the chosen move is worse than the best one and asks to confirm it
+// Returns whether the move should be played
+fn coach_move(game: &Game, solver: &mut Solver, row: usize, col: usize, piece: Option<Piece>) -> Result<bool, Error> {
+    // Moves the solver can't judge (or that will be rejected anyway) are let through
+    let piece = piece.unwrap_or_else(|| game.current_piece());
+    let moves = match solver.evaluate_moves(game) {
+        Ok(moves) => moves,
+        Err(_) => return Ok(true),
+    };
+    let best = moves.iter().map(|&(_, outcome)| outcome).max_by_key(|outcome| outcome.score());
+    let chosen = moves.iter().find(|&&(mv, _)| mv == Move {row, col, piece}).map(|&(_, outcome)| outcome);
 
-    // Printing gameboard
-    for (i, row) in tiles.iter().enumerate() {
-        // Print row headers
-        print!(" {}", i + 1);
+    let (best, chosen) = match (best, chosen) {
+        (Some(best), Some(chosen)) if chosen.rank() < best.rank() => (best, chosen),
+        _ => return Ok(true),
+    };
+    let (chosen_name, best_name) = (outcome_name(chosen), outcome_name(best));
+    let warning = match best {
+        Outcome::Win(_) => t!("coach.throws_win", chosen = chosen_name, best = best_name),
+        _ => t!("coach.throws_draw", chosen = chosen_name, best = best_name),
+    };
+    println!("{}", warning);
 
-        // Read actual enum values from the row
-        for tile in row {
-            print!(" {}", match *tile {
-                Some(Piece::X) => "x",
-                Some(Piece::O) => "o",
-                None => "\u{25A2}",
-            })
+    // Ask until we get a yes or no
+    loop {
+        accessible::prompt(&t!("coach.confirm"))?;
+        let answer = read_line()?;
+        match (is_answer(&answer, "y, yes", &t!("answer.yes")), is_answer(&answer, "n, no", &t!("answer.no"))) {
+            (true, _) => break Ok(true),
+            (_, true) => break Ok(false),
+            _ => {},
         }
-        println!(); // Spacing
     }
+}
 
-    println!(); // Spacing
+// Checks if something typed is one of the words for a command or answer, in English or the player's language
+// Both list the words separated by commas, e.g. "y, yes"
+fn is_answer(input: &str, english: &str, translated: &str) -> bool {
+    let input = input.trim().to_lowercase();
+    english.split(',').chain(translated.split(',')).any(|word| word.trim().to_lowercase() == input)
+}
+
+// Describes a result for the player about to move
+fn outcome_name(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Win(moves) => t!("outcome.win", moves = tn!("count.moves", moves)),
+        Outcome::Draw => t!("outcome.draw"),
+        Outcome::Loss(moves) => t!("outcome.loss", moves = tn!("count.moves", moves)),
+    }
+}
+
+// Print the game board
+fn print_tiles(tiles: &Tiles, players: &Players) {
+    if accessible::is_enabled() {
+        println!("{}", describe_tiles(tiles, players));
+        return;
+    }
+    print!("{}", render_tiles(tiles, players));
 }
@@ -0,0 +1,97 @@
+// The library's errors and descriptions in the player's language. The library's own Display impls stay
+// in English for other programs built on it, the game shows these instead.
+
+// Package Import Statements
+use tic_tac_toe::game::{MoveError, Piece, Players, SetupError, Tiles, Variant};
+use tic_tac_toe::{describe_tile_with, describe_tiles_with, tile_name, InvalidMove, InvalidMoveReason};
+use crate::i18n::t;
+
+// Explains why a move was refused, with the symbols the players actually use if we know them
+pub fn move_error<'a, P: Into<Option<&'a Players>>>(err: &MoveError, players: P) -> String {
+    let players = players.into();
+    let symbol = |piece: Piece| match players {
+        Some(players) if piece.index() < players.count() => players.symbol(piece).to_string(),
+        _ => piece.to_string(),
+    };
+    match *err {
+        MoveError::GameAlreadyOver => t!("move_error.game_over"),
+        MoveError::InvalidPosition {row, col} => t!("move_error.no_tile", tile = tile_name(row, col)),
+        MoveError::TileNotEmpty {other_piece, row, col} => t!("move_error.taken", tile = tile_name(row, col), piece = symbol(other_piece)),
+        MoveError::WrongPiece {piece} => t!("move_error.wrong_piece", piece = symbol(piece)),
+        MoveError::PieceRequired => t!("move_error.piece_required"),
+        ref other => other.with_symbols(players).to_string(), // Kinds added to the library later
+    }
+}
+
+// Quotes a move that couldn't be read back to the player, with what is wrong with it
+pub fn invalid_move(err: &InvalidMove) -> String {
+    t!("invalid_move", input = err.input, reason = invalid_move_reason(&err.reason))
+}
+
+// What is wrong with a move that couldn't be read
+pub fn invalid_move_reason(reason: &InvalidMoveReason) -> String {
+    match reason {
+        InvalidMoveReason::Empty => t!("invalid_move.empty"),
+        InvalidMoveReason::RowOutOfRange {row, size, zero_based: false} => t!("invalid_move.row", row = row, first = 1, last = size),
+        InvalidMoveReason::RowOutOfRange {row, size, zero_based: true} => t!("invalid_move.row", row = row, first = 0, last = size - 1),
+        InvalidMoveReason::ColumnOutOfRange {col, size, zero_based: false} => {
+            t!("invalid_move.column", col = col, first = "A", last = column_letter(size - 1))
+        },
+        InvalidMoveReason::ColumnOutOfRange {col, size, zero_based: true} => t!("invalid_move.column", col = col, first = 0, last = size - 1),
+        InvalidMoveReason::MissingColumn {row} => t!("invalid_move.missing_column", row = row),
+        InvalidMoveReason::MissingRow {col} => t!("invalid_move.missing_row", col = col),
+        InvalidMoveReason::NumpadKey {key} => t!("invalid_move.numpad_key", key = key),
+        InvalidMoveReason::NumpadBoard {size} => t!("invalid_move.numpad_board", size = size),
+        InvalidMoveReason::NoMiddle {size} => t!("invalid_move.no_middle", size = size),
+        InvalidMoveReason::UnknownWord {word} => t!("invalid_move.unknown_word", word = word),
+        InvalidMoveReason::UnknownPiece {symbol} => t!("invalid_move.unknown_piece", symbol = symbol),
+        InvalidMoveReason::Unrecognized => t!("invalid_move.unrecognized"),
+        other => other.to_string(),
+    }
+}
+
+// Explains why a game couldn't be set up, naming tiles the way they are typed
+pub fn setup_error(err: &SetupError) -> String {
+    match *err {
+        SetupError::TooFewPlayers {count} => t!("setup_error.too_few_players", count = count),
+        SetupError::TooManyPlayers {count} => t!("setup_error.too_many_players", count = count),
+        SetupError::DuplicateSymbol {symbol} => t!("setup_error.duplicate_symbol", symbol = symbol),
+        SetupError::InvalidTurnOrder => t!("setup_error.turn_order"),
+        SetupError::InvalidBoardSize {size} => t!("setup_error.board_size", size = size),
+        SetupError::InvalidWinLength {win_length, size} => t!("setup_error.win_length", win_length = win_length, size = size),
+        SetupError::UnsupportedPlayers {variant} => t!("setup_error.players", variant = variant_name(variant)),
+        SetupError::UnsupportedStartingPiece {variant} => t!("setup_error.starting_piece", variant = variant_name(variant)),
+        SetupError::UnknownPiece {piece} => t!("setup_error.unknown_piece", piece = piece),
+        SetupError::InvalidPosition {row, col} => t!("setup_error.no_tile", tile = tile_name(row, col)),
+        SetupError::TilePlacedTwice {row, col} => t!("setup_error.placed_twice", tile = tile_name(row, col)),
+        SetupError::MultipleWinners => t!("setup_error.multiple_winners"),
+        SetupError::ReservedSymbol {symbol} => t!("setup_error.reserved_symbol", symbol = symbol),
+        SetupError::UnknownSymbol {symbol} => t!("setup_error.unknown_symbol", symbol = symbol),
+        SetupError::InvalidBoardString => t!("setup_error.board_string"),
+        ref other => other.to_string(),
+    }
+}
+
+// Names a variant in the player's language
+pub fn variant_name(variant: Variant) -> String {
+    match variant {
+        Variant::Standard => t!("variant.standard"),
+        Variant::OrderAndChaos => t!("variant.order_chaos"),
+    }
+}
+
+// Describes a board in words for screen readers, one row per line: "Row 1: X, empty, empty"
+pub fn describe_tiles(tiles: &Tiles, players: &Players) -> String {
+    describe_tiles_with(tiles, players, &t!("tile.empty"), |number, tiles| t!("tile.row", number = number, tiles = tiles))
+}
+
+// Names a tile in words, e.g. "row 2, column B"
+pub fn describe_tile(row: usize, col: usize) -> String {
+    describe_tile_with(row, col, |row, col| t!("tile.name", row = row, col = col))
+}
+
+// The letter of a column, as it is written in tile names
+fn column_letter(col: usize) -> String {
+    let name = tile_name(0, col);
+    name[1..].to_string()
+}
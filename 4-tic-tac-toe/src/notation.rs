@@ -0,0 +1,322 @@
+// Move notation: a tile is written as its row number then its column letter (e.g. 2B for the centre of
+// a 3x3 board), followed by the piece when the player chooses it (e.g. "3D o" in Order and Chaos).
+// Players may also type B2, "2, b", zero-based pairs like "1,1", words like "top left", or numpad keys.
+
+// Standard Library Import Statements
+use std::error;
+use std::fmt;
+
+// Package Import Statements
+use crate::game::{Game, Move, Piece, Players, Variant};
+
+/// Input that isn't a move on the board: what was typed and what is wrong with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMove {
+    pub input: String,
+    pub reason: InvalidMoveReason,
+}
+
+/// Why a move couldn't be read. Rows and columns are kept the way they were typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidMoveReason {
+    Empty,
+    RowOutOfRange { row: String, size: usize, zero_based: bool },
+    ColumnOutOfRange { col: String, size: usize, zero_based: bool },
+    MissingColumn { row: String },         // e.g. "2"
+    MissingRow { col: String },            // e.g. "B"
+    NumpadKey { key: String },             // Only 1 to 9 are numpad keys
+    NumpadBoard { size: usize },           // The numpad only covers a 3x3 board
+    NoMiddle { size: usize },              // Boards with an even size have no middle row or column
+    UnknownWord { word: String },          // e.g. "centre" is fine, "centrer" is not
+    UnknownPiece { symbol: String },       // The piece after the tile isn't any player's symbol
+    Unrecognized,                          // Doesn't look like any way of writing a move
+}
+
+// Impl Display to quote the input back to the player and say what is wrong with it
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid move '{}': {}", self.input, self.reason)
+    }
+}
+
+impl error::Error for InvalidMove {}
+
+impl fmt::Display for InvalidMoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidMoveReason::Empty => write!(f, "no move was entered"),
+            InvalidMoveReason::RowOutOfRange {row, size, zero_based: false} => write!(f, "row {} out of range (rows go from 1 to {})", row, size),
+            InvalidMoveReason::RowOutOfRange {row, size, zero_based: true} => write!(f, "row {} out of range (rows go from 0 to {})", row, size - 1),
+            InvalidMoveReason::ColumnOutOfRange {col, size, zero_based: false} => {
+                write!(f, "column {} out of range (columns go from A to {})", col, column_letter(size - 1))
+            },
+            InvalidMoveReason::ColumnOutOfRange {col, size, zero_based: true} => {
+                write!(f, "column {} out of range (columns go from 0 to {})", col, size - 1)
+            },
+            InvalidMoveReason::MissingColumn {row} => write!(f, "row {} needs a column letter too (e.g. {}B)", row, row),
+            InvalidMoveReason::MissingRow {col} => write!(f, "column {} needs a row number too (e.g. 2{})", col, col),
+            InvalidMoveReason::NumpadKey {key} => write!(f, "there is no numpad key {} (use 1 to 9, 7 is the top left)", key),
+            InvalidMoveReason::NumpadBoard {size} => write!(f, "numpad keys only work on a 3x3 board, not {}x{}", size, size),
+            InvalidMoveReason::NoMiddle {size} => write!(f, "a {}x{} board has no middle row or column", size, size),
+            InvalidMoveReason::UnknownWord {word} => {
+                write!(f, "unknown word '{}' (try top, bottom, left, right or center)", word)
+            },
+            InvalidMoveReason::UnknownPiece {symbol} => write!(f, "no player uses the piece '{}'", symbol),
+            InvalidMoveReason::Unrecognized => {
+                write!(f, "expected a row number and a column letter (e.g. 1A, B2 or 2, b) or a tile like top left")
+            },
+        }
+    }
+}
+
+/// Names a tile by its row number and column letter, e.g. 2B
+pub fn tile_name(row: usize, col: usize) -> String {
+    format!("{}{}", row + 1, column_letter(col))
+}
+
+// Get the char `col` away from 'A' (i.e. A, B, or C), boards are at most 26 columns wide
+fn column_letter(col: usize) -> char {
+    char::from_u32('A' as u32 + col as u32).filter(|_| col < 26).unwrap_or('?')
+}
+
+/// Names a move the way a player would type it (e.g. 1A, or 3D o when the piece is chosen)
+pub fn move_name(game: &Game, mv: Move) -> String {
+    let tile = tile_name(mv.row, mv.col);
+    match game.variant() {
+        Variant::Standard => tile,
+        Variant::OrderAndChaos => format!("{} {}", tile, game.players().symbol(mv.piece)),
+    }
+}
+
+/// Parses a move with the usual settings, see MoveParser::parse
+///
+/// ```
+/// use tic_tac_toe::{parse_move, Piece, Players};
+///
+/// let players = Players::new(2).unwrap();
+/// assert_eq!(parse_move("2B", 3, &players).unwrap(), (1, 1, None));
+/// assert_eq!(parse_move("3c o", 3, &players).unwrap(), (2, 2, Some(Piece::O)));
+/// assert_eq!(parse_move("B2", 3, &players).unwrap(), (1, 1, None));
+/// assert_eq!(parse_move("2, b", 3, &players).unwrap(), (1, 1, None));
+/// assert_eq!(parse_move("0,2", 3, &players).unwrap(), (0, 2, None));
+/// assert_eq!(parse_move("top left", 3, &players).unwrap(), (0, 0, None));
+/// assert_eq!(parse_move("4A", 3, &players).unwrap_err().to_string(), "Invalid move '4A': row 4 out of range (rows go from 1 to 3)");
+/// ```
+pub fn parse_move(input: &str, size: usize, players: &Players) -> Result<(usize, usize, Option<Piece>), InvalidMove> {
+    MoveParser::new().parse(input, size, players)
+}
+
+/// Reads moves typed by players. A move names a tile, then optionally the piece to place (e.g. "3D o").
+/// Tiles can be written
+/// - row number then column letter, or the other way around, ignoring case: 2B, b2, "2, b"
+/// - as two numbers counting from 0, row first: "1,1", "0 2"
+/// - in words: center, top left, bottom, middle right, ...
+/// - as a single numpad key from 1 to 9 (7 is the top left), if the numpad is turned on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoveParser {
+    numpad: bool,
+}
+
+impl MoveParser {
+    /// Constructor for the usual settings: no numpad keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a single number from 1 to 9 as a key on a numpad laid out over a 3x3 board
+    pub fn numpad(mut self, numpad: bool) -> Self {
+        self.numpad = numpad;
+        self
+    }
+
+    /// Whether single numpad keys are read as tiles
+    pub fn is_numpad(&self) -> bool {
+        self.numpad
+    }
+
+    /// Parses the input for a tile on a `size` x `size` board and an optional piece,
+    /// returning the (row, col) of the tile counting from 0, and the piece if one was given
+    pub fn parse(&self, input: &str, size: usize, players: &Players) -> Result<(usize, usize, Option<Piece>), InvalidMove> {
+        let invalid = |reason| InvalidMove {input: input.to_string(), reason};
+        let chunks: Vec<&str> = input.split(|c: char| c.is_whitespace() || c == ',' || c == '-')
+            .filter(|chunk| !chunk.is_empty())
+            .collect();
+
+        // Most moves are just a tile
+        let whole = self.parse_tile(&chunks, size);
+        if let Ok((row, col)) = whole {
+            return Ok((row, col, None));
+        }
+
+        // Otherwise the last character on its own may be the piece to place
+        let (last, tile) = match chunks.split_last() {
+            Some((last, tile)) if !tile.is_empty() && last.chars().count() == 1 => (*last, tile),
+            _ => return whole.map(|(row, col)| (row, col, None)).map_err(invalid),
+        };
+        let symbol = last.chars().next().expect("chunks are never empty");
+        let piece = players.piece_for(symbol.to_ascii_lowercase()).or_else(|| players.piece_for(symbol));
+        match (self.parse_tile(tile, size), piece) {
+            (Ok((row, col)), Some(piece)) => Ok((row, col, Some(piece))),
+            (Ok(_), None) => Err(invalid(InvalidMoveReason::UnknownPiece {symbol: last.to_string()})),
+            (Err(reason), Some(_)) => Err(invalid(reason)),
+            (Err(_), None) => Err(invalid(whole.expect_err("the whole input was checked first"))),
+        }
+    }
+
+    // Reads the tile from the chunks of input between separators
+    fn parse_tile(&self, chunks: &[&str], size: usize) -> Result<(usize, usize), InvalidMoveReason> {
+        let tokens: Vec<Token> = chunks.iter().flat_map(|chunk| tokenize(chunk)).collect();
+
+        match &tokens[..] {
+            [] => Err(InvalidMoveReason::Empty),
+            [Token::Number(row), Token::Letters(col)] | [Token::Letters(col), Token::Number(row)] if col.len() == 1 => {
+                Ok((row_index(row, size)?, column_index(col, size)?))
+            },
+            // Two numbers have to be typed apart, "12" is just one number
+            [Token::Number(row), Token::Number(col)] => Ok((zero_based(row, size, true)?, zero_based(col, size, false)?)),
+            [Token::Number(key)] if self.numpad => numpad_tile(key, size),
+            [Token::Number(row)] => Err(InvalidMoveReason::MissingColumn {row: row.clone()}),
+            [Token::Letters(col)] if col.len() == 1 => Err(InvalidMoveReason::MissingRow {col: col.to_uppercase()}),
+            words if words.iter().all(|token| matches!(token, Token::Letters(word) if word.len() > 1)) => word_tile(words, size),
+            _ => Err(InvalidMoveReason::Unrecognized),
+        }
+    }
+}
+
+// Pieces of a tile: "B12" is the letters "B" then the number "12"
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Letters(String),
+    Other(char),
+}
+
+// Splits a chunk of input where it changes between letters, digits and anything else
+fn tokenize(chunk: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for c in chunk.chars() {
+        match (tokens.last_mut(), c) {
+            (Some(Token::Number(digits)), c) if c.is_ascii_digit() => digits.push(c),
+            (Some(Token::Letters(letters)), c) if c.is_ascii_alphabetic() => letters.push(c),
+            (_, c) if c.is_ascii_digit() => tokens.push(Token::Number(c.to_string())),
+            (_, c) if c.is_ascii_alphabetic() => tokens.push(Token::Letters(c.to_string())),
+            (_, c) => tokens.push(Token::Other(c)),
+        }
+    }
+    tokens
+}
+
+// Valid rows are 1 to size
+fn row_index(row: &str, size: usize) -> Result<usize, InvalidMoveReason> {
+    match row.parse::<usize>() {
+        Ok(number) if number >= 1 && number <= size => Ok(number - 1),
+        _ => Err(InvalidMoveReason::RowOutOfRange {row: row.to_string(), size, zero_based: false}),
+    }
+}
+
+// Valid cols are A onwards (ignoring case)
+fn column_index(col: &str, size: usize) -> Result<usize, InvalidMoveReason> {
+    let letter = col.to_ascii_uppercase();
+    match letter.as_bytes()[0].checked_sub(b'A') {
+        Some(index) if (index as usize) < size => Ok(index as usize),
+        _ => Err(InvalidMoveReason::ColumnOutOfRange {col: letter, size, zero_based: false}),
+    }
+}
+
+// Rows and columns written as numbers counting from 0
+fn zero_based(number: &str, size: usize, is_row: bool) -> Result<usize, InvalidMoveReason> {
+    match number.parse::<usize>() {
+        Ok(index) if index < size => Ok(index),
+        _ if is_row => Err(InvalidMoveReason::RowOutOfRange {row: number.to_string(), size, zero_based: true}),
+        _ => Err(InvalidMoveReason::ColumnOutOfRange {col: number.to_string(), size, zero_based: true}),
+    }
+}
+
+// Numpad keys laid over a 3x3 board: 7 8 9 on the top row, 1 2 3 on the bottom
+fn numpad_tile(key: &str, size: usize) -> Result<(usize, usize), InvalidMoveReason> {
+    if size != 3 {
+        return Err(InvalidMoveReason::NumpadBoard {size});
+    }
+    match key.parse::<usize>() {
+        Ok(key) if (1..=9).contains(&key) => Ok((2 - (key - 1) / 3, (key - 1) % 3)),
+        _ => Err(InvalidMoveReason::NumpadKey {key: key.to_string()}),
+    }
+}
+
+// Tiles named in words: one or two of top, bottom, left, right and center, e.g. "top left" or "center"
+// A row or column that isn't named is the middle one
+fn word_tile(words: &[Token], size: usize) -> Result<(usize, usize), InvalidMoveReason> {
+    let (mut row, mut col) = (None, None);
+    for word in words {
+        let word = match word {
+            Token::Letters(word) => word.to_lowercase(),
+            _ => return Err(InvalidMoveReason::Unrecognized),
+        };
+        let (axis, index) = match word.as_str() {
+            "top" | "upper" => (&mut row, Some(0)),
+            "bottom" | "lower" => (&mut row, Some(size - 1)),
+            "left" => (&mut col, Some(0)),
+            "right" => (&mut col, Some(size - 1)),
+            "center" | "centre" | "middle" => continue,
+            _ => return Err(InvalidMoveReason::UnknownWord {word}),
+        };
+        if axis.is_some() {
+            return Err(InvalidMoveReason::Unrecognized);
+        }
+        *axis = index;
+    }
+    if words.len() > 2 {
+        return Err(InvalidMoveReason::Unrecognized);
+    }
+
+    // Only boards with an odd size have a middle tile
+    let middle = || if size % 2 == 1 { Ok(size / 2) } else { Err(InvalidMoveReason::NoMiddle {size}) };
+    Ok((row.map_or_else(middle, Ok)?, col.map_or_else(middle, Ok)?))
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(input: &str, size: usize) -> Result<(usize, usize), InvalidMoveReason> {
+        parse_move(input, size, &Players::new(2).unwrap()).map(|(row, col, _)| (row, col)).map_err(|err| err.reason)
+    }
+
+    #[test]
+    fn every_way_of_writing_a_tile() {
+        for input in &["2B", "2b", "B2", "b2", "2 b", "2, b", "b-2", "1,1", "1 1", "center", "Middle", "centre middle"] {
+            assert_eq!(tile(input, 3), Ok((1, 1)), "{}", input);
+        }
+        assert_eq!(tile("top left", 3), Ok((0, 0)));
+        assert_eq!(tile("right bottom", 5), Ok((4, 4)));
+        assert_eq!(tile("top", 5), Ok((0, 2)));
+        assert_eq!(tile("12J", 12), Ok((11, 9)));
+        assert_eq!(tile("10, 11", 12), Ok((10, 11)));
+
+        let numpad = MoveParser::new().numpad(true);
+        let players = Players::new(2).unwrap();
+        assert_eq!(numpad.parse("7", 3, &players), Ok((0, 0, None)));
+        assert_eq!(numpad.parse("3 o", 3, &players), Ok((2, 2, Some(Piece::O))));
+        assert_eq!(numpad.parse("2B", 3, &players), Ok((1, 1, None)));
+    }
+
+    #[test]
+    fn errors_say_what_is_wrong() {
+        assert_eq!(tile("", 3), Err(InvalidMoveReason::Empty));
+        assert_eq!(tile("4A", 3), Err(InvalidMoveReason::RowOutOfRange {row: "4".into(), size: 3, zero_based: false}));
+        assert_eq!(tile("1d", 3), Err(InvalidMoveReason::ColumnOutOfRange {col: "D".into(), size: 3, zero_based: false}));
+        assert_eq!(tile("3,0", 3), Err(InvalidMoveReason::RowOutOfRange {row: "3".into(), size: 3, zero_based: true}));
+        assert_eq!(tile("2", 3), Err(InvalidMoveReason::MissingColumn {row: "2".into()}));
+        assert_eq!(tile("top top", 3), Err(InvalidMoveReason::Unrecognized));
+        assert_eq!(tile("top middle", 4), Err(InvalidMoveReason::NoMiddle {size: 4}));
+        assert_eq!(tile("top lft", 3), Err(InvalidMoveReason::UnknownWord {word: "lft".into()}));
+        assert_eq!(tile("2B q", 3), Err(InvalidMoveReason::UnknownPiece {symbol: "q".into()}));
+
+        let numpad = MoveParser::new().numpad(true);
+        let players = Players::new(2).unwrap();
+        assert_eq!(numpad.parse("5", 4, &players).unwrap_err().reason, InvalidMoveReason::NumpadBoard {size: 4});
+        assert_eq!(numpad.parse("0", 3, &players).unwrap_err().reason, InvalidMoveReason::NumpadKey {key: "0".into()});
+    }
+}
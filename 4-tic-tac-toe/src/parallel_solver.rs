@@ -0,0 +1,697 @@
+// Solves a whole game on several threads at once, for boards too big for the Solver to finish quickly
+// (4x4 with four in a row has millions of positions where 3x3 has a few thousand).
+//
+// The board is kept as two bitboards, the pieces of the player to move and those of the other player.
+// The two players have the same goal, so a position only depends on whose pieces are whose, not on
+// which symbol they use, and the eight rotations and reflections of a position are stored once
+// (see symmetry.rs). Solved positions go in a transposition table split into shards, each behind its
+// own lock, that every thread reads and fills.
+//
+// The positions a few moves in are handed out to the threads first, then the start is solved from
+// what they found. Every so often the table is saved to a checkpoint file, and a solve given the
+// same file carries on from it, so a long solve can be stopped and resumed.
+//
+// Checkpoint layout (numbers are little endian):
+//   magic         4 bytes  "TTTS"
+//   format        u8       CHECKPOINT_FORMAT_VERSION
+//   rules         u8       book::RULES_VERSION
+//   size          u8       board width and height
+//   win length    u8       pieces in a row needed to win
+//   entries       u64      number of entries that follow
+// Each entry:
+//   position      2 bits per tile (see Rules::key), padded to a whole byte
+//   outcome       u8       packed like in opening books (see book.rs)
+
+// Standard Library Import Statements
+use std::collections::{HashMap, HashSet};
+use std::convert::{TryFrom, TryInto};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, Move, Variant};
+use crate::book::{self, decode_outcome, encode_outcome};
+use crate::solver::{Outcome, SolveError};
+use crate::symmetry;
+
+// Marks the start of a checkpoint file
+const MAGIC: &[u8; 4] = b"TTTS";
+
+// Bump when the checkpoint layout changes
+const CHECKPOINT_FORMAT_VERSION: u8 = 1;
+
+// Boards up to 7x7, so a position fits in a u128 key and a game's length in an outcome byte
+const MAX_SIZE: usize = 7;
+
+// The transposition table is split into this many shards, so threads rarely wait for each other
+const SHARDS: usize = 64;
+
+// Stop handing out work once there are this many positions per thread (or the frontier is this deep)
+const WORK_PER_THREAD: usize = 16;
+const MAX_SPLIT_DEPTH: usize = 4;
+
+// Threads add their node counts to the shared totals in batches of this many
+const COUNT_BATCH: u64 = 4096;
+
+// Stop remembering positions after this many, rather than running out of memory
+const MAX_POSITIONS: usize = 200_000_000;
+
+// Define reasons a parallel solve couldn't finish
+#[derive(Debug)]
+pub enum ParallelSolveError {
+    Solve(SolveError),      // The position is finished, has too many players, or has too many positions
+    Unsupported,            // Only standard games on boards up to 7x7 can be solved
+    Interrupted,            // Stopped early. The checkpoint, if there is one, has everything solved so far
+    Io(io::Error),          // Reading or writing the checkpoint failed
+    BadCheckpoint { reason: String }, // The checkpoint is damaged or for a different game
+}
+
+impl From<SolveError> for ParallelSolveError {
+    fn from(err: SolveError) -> Self {
+        ParallelSolveError::Solve(err)
+    }
+}
+
+impl From<io::Error> for ParallelSolveError {
+    fn from(err: io::Error) -> Self {
+        ParallelSolveError::Io(err)
+    }
+}
+
+// How far a solve has got, passed to the progress callback
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub nodes: u64,          // Positions visited so far, counting repeats
+    pub positions: usize,    // Positions solved and stored in the table
+    pub elapsed: Duration,
+    pub checkpointed: bool,  // Whether the table was just saved
+}
+
+// What a finished solve found, and what it took
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub outcome: Outcome,    // For the player to move at the start
+    pub best_move: Move,
+    pub nodes: u64,          // Positions visited, counting repeats
+    pub table_hits: u64,     // Visits answered by the transposition table
+    pub positions: usize,    // Positions in the table at the end
+    pub resumed: usize,      // Positions loaded from the checkpoint
+    pub threads: usize,
+    pub elapsed: Duration,
+}
+
+// The rules of a board: which tiles are on it and the lines that win
+struct Rules {
+    size: usize,
+    win_length: usize,
+    full: u64,                    // A bit for every tile
+    lines: Vec<Vec<u64>>,         // For every tile, the winning lines through it
+    transforms: Vec<Vec<usize>>,  // For every symmetry, where each tile goes
+}
+
+impl Rules {
+    fn new(size: usize, win_length: usize) -> Self {
+        let cells = size * size;
+        let mut lines = vec![Vec::new(); cells];
+        let directions = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        for row in 0..size as isize {
+            for col in 0..size as isize {
+                for &(d_row, d_col) in &directions {
+                    let tiles: Vec<(isize, isize)> = (0..win_length as isize).map(|i| (row + d_row * i, col + d_col * i)).collect();
+                    if tiles.iter().all(|&(r, c)| r >= 0 && c >= 0 && r < size as isize && c < size as isize) {
+                        let mask = tiles.iter().fold(0, |mask, &(r, c)| mask | 1 << (r as usize * size + c as usize));
+                        tiles.iter().for_each(|&(r, c)| lines[r as usize * size + c as usize].push(mask));
+                    }
+                }
+            }
+        }
+
+        let transforms = (0..symmetry::TRANSFORMS)
+            .map(|t| (0..cells).map(|cell| {
+                let (row, col) = symmetry::transform(t, cell / size, cell % size, size);
+                row * size + col
+            }).collect())
+            .collect();
+        let full = if cells == 64 { u64::MAX } else { (1 << cells) - 1 };
+        Rules {size, win_length, full, lines, transforms}
+    }
+
+    // Checks if the piece just placed on `cell` completes a line
+    fn wins(&self, pieces: u64, cell: usize) -> bool {
+        self.lines[cell].iter().any(|&line| line & !pieces == 0)
+    }
+
+    // Moves every piece by a symmetry
+    fn transform(&self, t: usize, pieces: u64) -> u64 {
+        let mut moved = 0;
+        let mut rest = pieces;
+        while rest != 0 {
+            let cell = rest.trailing_zeros() as usize;
+            moved |= 1 << self.transforms[t][cell];
+            rest &= rest - 1;
+        }
+        moved
+    }
+
+    // The key of a position, the same for all of its rotations and reflections:
+    // the smallest of the transformed boards, with the mover's pieces above the other player's
+    fn key(&self, board: Board) -> u128 {
+        let cells = self.size * self.size;
+        (0..symmetry::TRANSFORMS)
+            .map(|t| (self.transform(t, board.mine) as u128) << cells | self.transform(t, board.theirs) as u128)
+            .min()
+            .expect("there is always at least one transform")
+    }
+
+    // Bytes a key takes in a checkpoint
+    fn key_bytes(&self) -> usize {
+        (2 * self.size * self.size).div_ceil(8)
+    }
+}
+
+// A position: the pieces of the player to move, and of the other player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Board {
+    mine: u64,
+    theirs: u64,
+}
+
+impl Board {
+    // Plays on a tile and turns the board around for the other player
+    fn play(self, cell: usize) -> Board {
+        Board {mine: self.theirs, theirs: self.mine | 1 << cell}
+    }
+}
+
+// Solved positions shared by every thread, split into shards that each have their own lock
+struct Table {
+    shards: Vec<Mutex<HashMap<u128, Outcome>>>,
+    len: AtomicUsize,
+}
+
+impl Table {
+    fn new() -> Self {
+        Table {
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    // Spreads keys over the shards, mixing the bits so similar positions land in different ones
+    fn shard(&self, key: u128) -> &Mutex<HashMap<u128, Outcome>> {
+        let mixed = (key as u64 ^ (key >> 64) as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        &self.shards[(mixed >> 58) as usize % SHARDS]
+    }
+
+    fn get(&self, key: u128) -> Option<Outcome> {
+        self.shard(key).lock().expect("no thread panics holding a shard").get(&key).copied()
+    }
+
+    fn insert(&self, key: u128, outcome: Outcome) {
+        // Two threads can solve the same position at once, it only counts once
+        if self.shard(key).lock().expect("no thread panics holding a shard").insert(key, outcome).is_none() {
+            self.len.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+}
+
+// Node counts, kept per thread and added to the shared totals now and then
+#[derive(Default)]
+struct Counts {
+    nodes: u64,
+    hits: u64,
+}
+
+// The state every thread of one solve shares
+struct Search<'a> {
+    rules: &'a Rules,
+    table: &'a Table,
+    stop: &'a AtomicBool,
+    nodes: &'a AtomicU64,
+    hits: &'a AtomicU64,
+    max_positions: usize,
+}
+
+impl Search<'_> {
+    // Finds the best result for the player to move, with perfect play from both sides
+    fn solve(&self, board: Board, counts: &mut Counts) -> Result<Outcome, ParallelSolveError> {
+        counts.nodes += 1;
+        if counts.nodes.is_multiple_of(COUNT_BATCH) {
+            self.flush(counts);
+            if self.stop.load(Ordering::Relaxed) {
+                return Err(ParallelSolveError::Interrupted);
+            }
+        }
+
+        let key = self.rules.key(board);
+        if let Some(outcome) = self.table.get(key) {
+            counts.hits += 1;
+            return Ok(outcome);
+        }
+        if self.table.len() >= self.max_positions {
+            return Err(SolveError::TooLarge {max_positions: self.max_positions}.into());
+        }
+
+        let mut best: Option<Outcome> = None;
+        let mut empty = self.rules.full & !(board.mine | board.theirs);
+        while empty != 0 {
+            let cell = empty.trailing_zeros() as usize;
+            empty &= empty - 1;
+
+            let outcome = self.outcome_after(board, cell, counts)?;
+            if best.is_none_or(|best| outcome.score() > best.score()) {
+                best = Some(outcome);
+            }
+            // Nothing beats winning straight away
+            if outcome == Outcome::Win(1) {
+                break;
+            }
+        }
+
+        let best = best.expect("unfinished games always have an empty tile");
+        self.table.insert(key, best);
+        Ok(best)
+    }
+
+    // Finds the result of playing on a tile, for the player playing it
+    fn outcome_after(&self, board: Board, cell: usize, counts: &mut Counts) -> Result<Outcome, ParallelSolveError> {
+        let next = board.play(cell);
+        if self.rules.wins(next.theirs, cell) {
+            Ok(Outcome::Win(1))
+        }
+        else if next.mine | next.theirs == self.rules.full {
+            Ok(Outcome::Draw)
+        }
+        else {
+            Ok(self.solve(next, counts)?.flip())
+        }
+    }
+
+    // Solves positions from the shared list, taking the next one until there are none left
+    fn work(&self, positions: &[Board], next: &AtomicUsize) -> Result<(), ParallelSolveError> {
+        let mut counts = Counts::default();
+        let mut result = Ok(());
+        while let Some(&board) = positions.get(next.fetch_add(1, Ordering::Relaxed)) {
+            result = self.solve(board, &mut counts).map(|_| ());
+            if result.is_err() {
+                break;
+            }
+        }
+        self.flush(&mut counts);
+        result
+    }
+
+    // Adds a thread's counts to the shared totals
+    fn flush(&self, counts: &mut Counts) {
+        self.nodes.fetch_add(counts.nodes, Ordering::Relaxed);
+        self.hits.fetch_add(counts.hits, Ordering::Relaxed);
+        *counts = Counts::default();
+    }
+}
+
+// Solves a whole game from its current position on several threads
+pub struct ParallelSolver {
+    threads: usize,
+    checkpoint: Option<PathBuf>,
+    interval: Duration,
+    stop: Arc<AtomicBool>,
+    progress: Option<Box<dyn Fn(Progress) + Send + Sync>>,
+}
+
+impl ParallelSolver {
+    // Constructor, using a thread for every core
+    pub fn new() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            checkpoint: None,
+            interval: Duration::from_secs(60),
+            stop: Arc::new(AtomicBool::new(false)),
+            progress: None,
+        }
+    }
+
+    // Searches on this many threads (at least one)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // How many threads the search runs on
+    pub fn thread_count(&self) -> usize {
+        self.threads
+    }
+
+    // Saves progress to this file every `interval`, and carries on from it if it already exists
+    pub fn checkpoint(mut self, path: &Path, interval: Duration) -> Self {
+        self.checkpoint = Some(path.to_path_buf());
+        self.interval = interval;
+        self
+    }
+
+    // Called with the progress every interval (and after every checkpoint)
+    pub fn on_progress<F: Fn(Progress) + Send + Sync + 'static>(mut self, progress: F) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    // Setting the flag stops the solve, e.g. from a Ctrl-C handler. The checkpoint is saved first.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    // Solves the game for the player about to move
+    pub fn solve(&self, game: &Game) -> Result<Report, ParallelSolveError> {
+        let (rules, root) = self.check(game)?;
+        let started = Instant::now();
+
+        // Start from whatever an earlier solve of the same board got through
+        let table = Table::new();
+        let resumed = match &self.checkpoint {
+            Some(path) if path.exists() => load_checkpoint(path, &rules, &table)?,
+            _ => 0,
+        };
+
+        let (nodes, hits) = (AtomicU64::new(0), AtomicU64::new(0));
+        let search = Search {rules: &rules, table: &table, stop: &self.stop, nodes: &nodes, hits: &hits, max_positions: MAX_POSITIONS};
+        let work = frontier(&rules, root, self.threads);
+        let next = AtomicUsize::new(0);
+
+        let (search, work, next) = (&search, &work, &next);
+        let result = thread::scope(|scope| {
+            // Reports progress and saves the table every interval, until the workers are done
+            let (done, timer) = mpsc::channel::<()>();
+            let saver = scope.spawn(move || loop {
+                match timer.recv_timeout(self.interval) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        let saved = match &self.checkpoint {
+                            Some(path) => save_checkpoint(path, search.rules, search.table).map(|()| true),
+                            None => Ok(false),
+                        };
+                        self.report_progress(search, started, saved.as_ref().is_ok_and(|&saved| saved));
+                        if saved.is_err() {
+                            // No point searching on if the work can't be kept
+                            self.stop.store(true, Ordering::Relaxed);
+                            return saved.map(|_| ());
+                        }
+                    },
+                    _ => return Ok(()),
+                }
+            });
+
+            // Every worker takes the next unsolved position until there are none left
+            let workers: Vec<_> = (0..self.threads).map(|_| scope.spawn(move || search.work(work, next))).collect();
+            let worked = workers.into_iter().try_for_each(|worker| worker.join().expect("solver threads don't panic"));
+            drop(done);
+            saver.join().expect("the checkpoint thread doesn't panic")?;
+            worked
+        });
+
+        // Save what was solved before giving up, so none of it has to be searched again
+        if let Err(err) = result {
+            if let Some(path) = &self.checkpoint {
+                save_checkpoint(path, &rules, &table)?;
+            }
+            return Err(err);
+        }
+
+        // The start itself, and the move that gets its result, come straight from the table now
+        let mut counts = Counts::default();
+        let outcome = search.solve(root, &mut counts)?;
+        let mut best = None;
+        let mut empty = rules.full & !(root.mine | root.theirs);
+        while empty != 0 {
+            let cell = empty.trailing_zeros() as usize;
+            empty &= empty - 1;
+            if search.outcome_after(root, cell, &mut counts)? == outcome {
+                best = Some(cell);
+                break;
+            }
+        }
+        search.flush(&mut counts);
+        let cell = best.expect("the best result comes from one of the moves");
+
+        // Save everything, the start included, so solving again is instant
+        if let Some(path) = &self.checkpoint {
+            save_checkpoint(path, &rules, &table)?;
+        }
+
+        Ok(Report {
+            outcome,
+            best_move: Move {row: cell / rules.size, col: cell % rules.size, piece: game.current_piece()},
+            nodes: nodes.load(Ordering::Relaxed),
+            table_hits: hits.load(Ordering::Relaxed),
+            positions: table.len(),
+            resumed,
+            threads: self.threads,
+            elapsed: started.elapsed(),
+        })
+    }
+
+    // Checks the game is one this solver handles, and turns it into bitboards
+    fn check(&self, game: &Game) -> Result<(Rules, Board), ParallelSolveError> {
+        if game.is_finished() {
+            return Err(SolveError::GameAlreadyOver.into());
+        }
+        if game.players().count() != 2 {
+            return Err(SolveError::UnsupportedPlayers {count: game.players().count()}.into());
+        }
+        if game.variant() != Variant::Standard || game.tiles().len() > MAX_SIZE {
+            return Err(ParallelSolveError::Unsupported);
+        }
+
+        let rules = Rules::new(game.tiles().len(), game.win_length());
+        let mut board = Board {mine: 0, theirs: 0};
+        for (row, tiles_row) in game.tiles().iter().enumerate() {
+            for (col, tile) in tiles_row.iter().enumerate() {
+                let bit = 1 << (row * rules.size + col);
+                match tile {
+                    Some(piece) if *piece == game.current_piece() => board.mine |= bit,
+                    Some(_) => board.theirs |= bit,
+                    None => {},
+                }
+            }
+        }
+        Ok((rules, board))
+    }
+
+    // Passes the progress so far to the callback, if there is one
+    fn report_progress(&self, search: &Search, started: Instant, checkpointed: bool) {
+        if let Some(progress) = &self.progress {
+            progress(Progress {
+                nodes: search.nodes.load(Ordering::Relaxed),
+                positions: search.table.len(),
+                elapsed: started.elapsed(),
+                checkpointed,
+            });
+        }
+    }
+}
+
+// Lists the unfinished positions a few moves in, one per symmetry, for the threads to share out.
+// Goes one move deeper at a time until there is enough work for every thread.
+fn frontier(rules: &Rules, root: Board, threads: usize) -> Vec<Board> {
+    let mut positions = vec![root];
+    for _ in 0..MAX_SPLIT_DEPTH {
+        if positions.len() >= threads * WORK_PER_THREAD {
+            break;
+        }
+        let mut seen = HashSet::new();
+        let mut next = Vec::new();
+        for &board in &positions {
+            let mut empty = rules.full & !(board.mine | board.theirs);
+            while empty != 0 {
+                let cell = empty.trailing_zeros() as usize;
+                empty &= empty - 1;
+                let child = board.play(cell);
+                let finished = rules.wins(child.theirs, cell) || child.mine | child.theirs == rules.full;
+                if !finished && seen.insert(rules.key(child)) {
+                    next.push(child);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        positions = next;
+    }
+    positions
+}
+
+// Writes every solved position to the checkpoint file, replacing it only once the new one is complete
+fn save_checkpoint(path: &Path, rules: &Rules, table: &Table) -> Result<(), ParallelSolveError> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[CHECKPOINT_FORMAT_VERSION, book::RULES_VERSION, rules.size as u8, rules.win_length as u8]);
+    let count_at = bytes.len();
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+
+    let mut count: u64 = 0;
+    for shard in &table.shards {
+        let shard = shard.lock().expect("no thread panics holding a shard");
+        for (&key, &outcome) in shard.iter() {
+            bytes.extend_from_slice(&key.to_le_bytes()[..rules.key_bytes()]);
+            bytes.push(encode_outcome(outcome).expect("games on boards up to 7x7 fit in an outcome byte"));
+            count += 1;
+        }
+    }
+    bytes[count_at..count_at + 8].copy_from_slice(&count.to_le_bytes());
+
+    let partial = path.with_extension("partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+// Fills the table from a checkpoint file, returning how many positions it had
+fn load_checkpoint(path: &Path, rules: &Rules, table: &Table) -> Result<usize, ParallelSolveError> {
+    let bytes = fs::read(path)?;
+    let bad = |reason: &str| ParallelSolveError::BadCheckpoint {reason: reason.to_string()};
+
+    if bytes.len() < 16 || &bytes[..4] != MAGIC {
+        return Err(bad("not a checkpoint file"));
+    }
+    if bytes[4] != CHECKPOINT_FORMAT_VERSION {
+        return Err(bad("written by a different version of this program"));
+    }
+    if bytes[5] != book::RULES_VERSION {
+        return Err(bad("made with different rules"));
+    }
+    if (bytes[6] as usize, bytes[7] as usize) != (rules.size, rules.win_length) {
+        return Err(ParallelSolveError::BadCheckpoint {
+            reason: format!("made for a {}x{} board with {} in a row", bytes[6], bytes[6], bytes[7]),
+        });
+    }
+
+    // The count comes from the file, so it can't be trusted to fit or to multiply without overflowing
+    let count = u64::from_le_bytes(bytes[8..16].try_into().expect("8 bytes"));
+    let entry_size = rules.key_bytes() + 1;
+    let entries = &bytes[16..];
+    let expected = usize::try_from(count).ok().and_then(|count| count.checked_mul(entry_size));
+    if expected != Some(entries.len()) {
+        return Err(bad("the file is cut short or has extra bytes"));
+    }
+    for entry in entries.chunks(entry_size) {
+        let mut key = [0; 16];
+        key[..rules.key_bytes()].copy_from_slice(&entry[..rules.key_bytes()]);
+        let outcome = decode_outcome(entry[rules.key_bytes()]).ok_or_else(|| bad("an outcome is out of range"))?;
+        table.insert(u128::from_le_bytes(key), outcome);
+    }
+    Ok(entries.len() / entry_size)
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use tic_tac_toe::game::GameBuilder;
+
+    // A game on a board given as rows, e.g. "xo./.x./..." (. for empty)
+    fn game(board: &str, win_length: usize) -> Game {
+        GameBuilder::new().board_string(board).unwrap().win_length(win_length).build().unwrap()
+    }
+
+    // A checkpoint file no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("tic-tac-toe-solve-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn agrees_with_the_solver() {
+        let positions = [
+            (".../.../...", 3),
+            ("x../.o./...", 3),
+            ("xo./.x./... o", 3),
+            ("xo../.xo./..o./x...", 4),
+            ("xoxo/ox../..../....", 4),
+            ("xo../ox../.x../.o..", 3),
+        ];
+        for (board, win_length) in positions {
+            let game = game(board, win_length);
+            let moves = Solver::new().evaluate_moves(&game).unwrap();
+            let best = moves.iter().map(|&(_, outcome)| outcome).max_by_key(|outcome| outcome.score()).unwrap();
+            for threads in [1, 4] {
+                let report = ParallelSolver::new().threads(threads).solve(&game).unwrap();
+                assert_eq!(report.outcome, best, "{} on {} threads", board, threads);
+                assert!(moves.contains(&(report.best_move, best)), "{} on {} threads", board, threads);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_positions_share_a_key() {
+        let rules = Rules::new(4, 4);
+        let board = Board {mine: 0b0011, theirs: 0b1_0000};
+        let keys: HashSet<u128> = (0..symmetry::TRANSFORMS)
+            .map(|t| rules.key(Board {mine: rules.transform(t, board.mine), theirs: rules.transform(t, board.theirs)}))
+            .collect();
+        assert_eq!(keys.len(), 1);
+        assert_ne!(rules.key(board), rules.key(Board {mine: board.theirs, theirs: board.mine}));
+    }
+
+    #[test]
+    fn resuming_from_a_checkpoint_searches_less() {
+        let path = temp_path("resume.checkpoint");
+        let game = game("xo../.xo./..../....", 4);
+        let solver = ParallelSolver::new().threads(2).checkpoint(&path, Duration::from_secs(60));
+
+        let first = solver.solve(&game).unwrap();
+        assert_eq!(first.resumed, 0);
+        let second = solver.solve(&game).unwrap();
+        assert_eq!(second.outcome, first.outcome);
+        assert_eq!(second.resumed, first.positions);
+        assert!(second.nodes < first.nodes, "{} nodes after resuming, {} before", second.nodes, first.nodes);
+
+        // Checkpoints only fit the board they were made for
+        let other = game_on("....../....../....../....../....../......");
+        assert!(matches!(solver.solve(&other), Err(ParallelSolveError::BadCheckpoint {..})));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn checkpoints_with_a_bad_count_are_rejected() {
+        let path = temp_path("count.checkpoint");
+        let rules = Rules::new(4, 4);
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[CHECKPOINT_FORMAT_VERSION, book::RULES_VERSION, 4, 4]);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[0; 5]);
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(load_checkpoint(&path, &rules, &Table::new()), Err(ParallelSolveError::BadCheckpoint {..})));
+
+        // A count that multiplies out to the bytes that are left once it wraps around: (2^64 + 4) / 5 entries
+        bytes[8..16].copy_from_slice(&(u64::MAX / 5 + 1).to_le_bytes());
+        bytes.truncate(20);
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(load_checkpoint(&path, &rules, &Table::new()), Err(ParallelSolveError::BadCheckpoint {..})));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn a_stopped_solve_keeps_its_progress() {
+        let path = temp_path("stopped.checkpoint");
+        let game = game("..../..../..../....", 3);
+        let solver = ParallelSolver::new().threads(2).checkpoint(&path, Duration::from_secs(60));
+        solver.stop_flag().store(true, Ordering::Relaxed);
+        assert!(matches!(solver.solve(&game), Err(ParallelSolveError::Interrupted)));
+        assert!(path.exists());
+        let _ = fs::remove_file(&path);
+    }
+
+    // An empty game on a board given as rows, with the default win length
+    fn game_on(board: &str) -> Game {
+        GameBuilder::new().board_string(board).unwrap().build().unwrap()
+    }
+}
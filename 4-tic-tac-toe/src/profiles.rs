@@ -0,0 +1,331 @@
+// Player profiles with Elo ratings, kept in a data file between sessions
+//
+// The file is plain text with one tab separated record per line:
+//   player  <name>  <rating>  <wins>  <losses>  <ties>  <game ids, comma separated>
+//   game    <id>  <unix time>  <player names, comma separated>  <winner name, or empty for a tie>  <final board>
+
+// Standard Library Import Statements
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package Import Statements
+use crate::i18n::t;
+
+// Header written at the top of the file
+const HEADER: &str = "# tic-tac-toe profiles v1";
+
+// Rating given to new players
+pub const STARTING_RATING: f64 = 1200.0;
+
+// How far one game can move a rating
+const K_FACTOR: f64 = 32.0;
+
+// Define a Profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub rating: f64,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    pub games: Vec<String>, // Ids of the games this player was in, oldest first
+}
+
+impl Profile {
+    // Constructor for a player who hasn't played yet
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rating: STARTING_RATING,
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            games: Vec::new(),
+        }
+    }
+}
+
+// A finished game, referenced from the profiles of everyone who played it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub id: String,
+    pub time: u64, // Seconds since the Unix epoch
+    pub players: Vec<String>,
+    pub winner: Option<String>, // None for a tie
+    pub board: String, // The final position in board string notation
+}
+
+// Every profile and game in the data file
+#[derive(Debug, Clone)]
+pub struct ProfileStore {
+    path: PathBuf,
+    profiles: Vec<Profile>,
+    games: Vec<GameRecord>,
+}
+
+impl ProfileStore {
+    // Where the data file lives: $XDG_DATA_HOME/tic-tac-toe, or ~/.local/share/tic-tac-toe
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")))?;
+        Some(data_home.join("tic-tac-toe").join("profiles.tsv"))
+    }
+
+    // Reads the data file, a missing file is the same as an empty one
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut store = Self {path: path.to_path_buf(), profiles: Vec::new(), games: Vec::new()};
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(err) => return Err(err),
+        };
+
+        for (number, line) in contents.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, t!("file.invalid_record", path = path.display(), line = number + 1));
+            let fields: Vec<&str> = line.split('\t').collect();
+
+            match fields[..] {
+                ["player", name, rating, wins, losses, ties, games] => store.profiles.push(Profile {
+                    name: name.to_string(),
+                    rating: rating.parse().map_err(|_| invalid())?,
+                    wins: wins.parse().map_err(|_| invalid())?,
+                    losses: losses.parse().map_err(|_| invalid())?,
+                    ties: ties.parse().map_err(|_| invalid())?,
+                    games: split_list(games),
+                }),
+                ["game", id, time, players, winner, board] => store.games.push(GameRecord {
+                    id: id.to_string(),
+                    time: time.parse().map_err(|_| invalid())?,
+                    players: split_list(players),
+                    winner: if winner.is_empty() { None } else { Some(winner.to_string()) },
+                    board: board.to_string(),
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(store)
+    }
+
+    // Loads the data file, lets `change` update it and saves it again, keeping other games out in between
+    // so that two games finishing at once can't lose each other's results
+    pub fn update<F: FnOnce(&mut Self)>(path: &Path, change: F) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let _lock = lock(path)?;
+        let mut store = Self::load(path)?;
+        change(&mut store);
+        store.save()
+    }
+
+    // Writes the data file atomically: a crash leaves either the old file or the new one, never half of one
+    pub fn save(&self) -> io::Result<()> {
+        let mut contents = format!("{}\n", HEADER);
+        for profile in &self.profiles {
+            contents += &format!(
+                "player\t{}\t{:.3}\t{}\t{}\t{}\t{}\n",
+                profile.name, profile.rating, profile.wins, profile.losses, profile.ties, profile.games.join(","),
+            );
+        }
+        for game in &self.games {
+            contents += &format!(
+                "game\t{}\t{}\t{}\t{}\t{}\n",
+                game.id, game.time, game.players.join(","), game.winner.as_deref().unwrap_or(""), game.board,
+            );
+        }
+
+        // Write everything to a temporary file next to the real one, then swap it in.
+        // The temporary file is named after this process, so another game saving at the same time can't write over it.
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp_path = sibling(&self.path, &format!(".{}.tmp", process::id()));
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &self.path)
+    }
+
+    // Records a finished game and updates everyone's rating and record
+    // `winner` is an index into `players`, None for a tie
+    pub fn record_game(&mut self, players: &[String], winner: Option<usize>, board: String) {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+        let id = format!("{}-{}", time, self.games.len() + 1);
+        let indices: Vec<usize> = players.iter().map(|name| self.profile_index(name)).collect();
+
+        // Elo, one pairing at a time: the winner beat everyone else, everyone else drew with each other
+        let ratings: Vec<f64> = indices.iter().map(|&i| self.profiles[i].rating).collect();
+        let k = K_FACTOR / (players.len() - 1) as f64;
+        for (a, &index) in indices.iter().enumerate() {
+            let mut change = 0.0;
+            for b in 0..players.len() {
+                if a == b {
+                    continue;
+                }
+                let expected = 1.0 / (1.0 + 10f64.powf((ratings[b] - ratings[a]) / 400.0));
+                let score = match winner {
+                    Some(w) if w == a => 1.0,
+                    Some(w) if w == b => 0.0,
+                    _ => 0.5,
+                };
+                change += k * (score - expected);
+            }
+
+            let profile = &mut self.profiles[index];
+            profile.rating += change;
+            match winner {
+                Some(w) if w == a => profile.wins += 1,
+                Some(_) => profile.losses += 1,
+                None => profile.ties += 1,
+            }
+            profile.games.push(id.clone());
+        }
+
+        self.games.push(GameRecord {
+            id,
+            time,
+            players: players.to_vec(),
+            winner: winner.map(|w| players[w].clone()),
+            board,
+        });
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn game(&self, id: &str) -> Option<&GameRecord> {
+        self.games.iter().find(|game| game.id == id)
+    }
+
+    // Every profile, highest rating first
+    pub fn leaderboard(&self) -> Vec<&Profile> {
+        let mut profiles: Vec<&Profile> = self.profiles.iter().collect();
+        profiles.sort_by(|a, b| b.rating.total_cmp(&a.rating).then_with(|| a.name.cmp(&b.name)));
+        profiles
+    }
+
+    // Finds a profile by name, creating it if this is their first game
+    fn profile_index(&mut self, name: &str) -> usize {
+        match self.profiles.iter().position(|profile| profile.name == name) {
+            Some(index) => index,
+            None => {
+                self.profiles.push(Profile::new(name));
+                self.profiles.len() - 1
+            },
+        }
+    }
+}
+
+// Waits until no other game is updating the profiles, then keeps them out until the returned file is dropped.
+// The lock is on a file next to the data file, since saving replaces the data file itself.
+fn lock(path: &Path) -> io::Result<File> {
+    let file = OpenOptions::new().create(true).truncate(false).write(true).open(sibling(path, ".lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
+// A file next to `path` with `suffix` added to its name, e.g. profiles.tsv.lock
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// Cleans up a player name so it can't break the file format
+pub fn clean_name(name: &str) -> String {
+    name.trim().replace(['\t', ','], " ")
+}
+
+// Splits a comma separated field, an empty field is an empty list
+fn split_list(field: &str) -> Vec<String> {
+    field.split(',').filter(|item| !item.is_empty()).map(String::from).collect()
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A data file no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("tic-tac-toe-profiles-{}-{}", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn rating(store: &ProfileStore, name: &str) -> f64 {
+        store.profile(name).unwrap().rating
+    }
+
+    #[test]
+    fn ratings_follow_elo() {
+        let mut store = ProfileStore::load(&temp_path("elo.tsv")).unwrap();
+
+        // Evenly matched players trade half the K factor
+        store.record_game(&names(&["Alice", "Bob"]), Some(0), String::new());
+        assert_eq!(rating(&store, "Alice"), STARTING_RATING + K_FACTOR / 2.0);
+        assert_eq!(rating(&store, "Bob"), STARTING_RATING - K_FACTOR / 2.0);
+
+        // A tie moves the favourite down and the underdog up by the same amount
+        store.record_game(&names(&["Alice", "Bob"]), None, String::new());
+        let gained = rating(&store, "Bob") - (STARTING_RATING - K_FACTOR / 2.0);
+        assert!(gained > 0.0);
+        assert!((rating(&store, "Alice") - (STARTING_RATING + K_FACTOR / 2.0) + gained).abs() < 1e-9);
+
+        let alice = store.profile("Alice").unwrap();
+        assert_eq!((alice.wins, alice.losses, alice.ties), (1, 0, 1));
+        assert_eq!(alice.games.len(), 2);
+    }
+
+    #[test]
+    fn ratings_with_more_players_add_up() {
+        let mut store = ProfileStore::load(&temp_path("three.tsv")).unwrap();
+        store.record_game(&names(&["Alice", "Bob", "Carol"]), Some(2), String::new());
+
+        // The winner takes what the others lose, and the others drew with each other
+        assert_eq!(rating(&store, "Carol"), STARTING_RATING + K_FACTOR / 2.0);
+        assert_eq!(rating(&store, "Alice"), STARTING_RATING - K_FACTOR / 4.0);
+        assert_eq!(rating(&store, "Bob"), STARTING_RATING - K_FACTOR / 4.0);
+        assert_eq!(store.leaderboard()[0].name, "Carol");
+        assert_eq!(store.game(&store.profile("Bob").unwrap().games[0]).unwrap().winner.as_deref(), Some("Carol"));
+    }
+
+    #[test]
+    fn profiles_survive_a_round_trip() {
+        let path = temp_path("round-trip.tsv");
+        ProfileStore::update(&path, |store| store.record_game(&names(&["Alice", "Bob"]), Some(1), "xo./.x./...".to_string())).unwrap();
+        ProfileStore::update(&path, |store| store.record_game(&names(&["Bob", "Carol"]), None, "xox/oxo/oxo".to_string())).unwrap();
+
+        let loaded = ProfileStore::load(&path).unwrap();
+        let mut saved = ProfileStore::load(&temp_path("empty.tsv")).unwrap();
+        saved.record_game(&names(&["Alice", "Bob"]), Some(1), "xo./.x./...".to_string());
+        saved.record_game(&names(&["Bob", "Carol"]), None, "xox/oxo/oxo".to_string());
+        for name in ["Alice", "Bob", "Carol"] {
+            let (loaded, saved) = (loaded.profile(name).unwrap(), saved.profile(name).unwrap());
+            assert!((loaded.rating - saved.rating).abs() < 0.001, "{}", name);
+            assert_eq!((loaded.wins, loaded.losses, loaded.ties), (saved.wins, saved.losses, saved.ties));
+        }
+        let game = loaded.game(&loaded.profile("Carol").unwrap().games[0]).unwrap();
+        assert_eq!((game.players.clone(), game.winner.clone(), game.board.as_str()), (names(&["Bob", "Carol"]), None, "xox/oxo/oxo"));
+
+        // The temporary file has been renamed into place, and broken records are errors
+        assert!(!sibling(&path, &format!(".{}.tmp", process::id())).exists());
+        fs::write(&path, "player\tAlice\tnot a rating\t0\t0\t0\t\n").unwrap();
+        assert_eq!(ProfileStore::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,182 @@
+// Finds "win in N" puzzles: positions where the player to move can force a win,
+// but only one first move does it
+
+// Standard Library Import Statements
+use std::collections::HashSet;
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, Move};
+use crate::solver::{Outcome, SolveError, Solver};
+use crate::symmetry;
+
+// Hardest difficulty rating
+pub const MAX_DIFFICULTY: u32 = 5;
+
+// Define a Puzzle
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub game: Game,          // The position, with the solving player to move
+    pub moves: usize,        // How many of their own moves the player needs to win
+    pub solution: Vec<Move>, // The winning move, the best defence, the next winning move, ...
+    pub lines: usize,        // How many different defences the solution has to answer
+    pub difficulty: u32,     // 1 to MAX_DIFFICULTY, going up as the solution tree gets wider
+}
+
+// Finds every position reachable from `start` (counting rotations and reflections once)
+// where the player to move wins in exactly `moves` of their own moves with only one winning first move
+pub fn generate(start: &Game, moves: usize, solver: &mut Solver) -> Result<Vec<Puzzle>, SolveError> {
+    let mut puzzles = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![start.clone()];
+
+    // A win in `moves` of our own moves also takes the opponent's replies in between
+    let plies = moves * 2 - 1;
+
+    while let Some(game) = stack.pop() {
+        if game.is_finished() || !seen.insert((symmetry::canonical_cells(game.tiles()).0, game.current_piece())) {
+            continue;
+        }
+
+        // Exactly one move may win, and the fastest win has to take exactly `moves` moves
+        let evaluated = solver.evaluate_moves(&game)?;
+        let winning: Vec<(Move, Outcome)> = evaluated.into_iter()
+            .filter(|&(_, outcome)| matches!(outcome, Outcome::Win(_)))
+            .collect();
+        if let [(first, Outcome::Win(length))] = winning[..] {
+            if length == plies {
+                puzzles.push(new_puzzle(&game, moves, first, solver)?);
+            }
+        }
+
+        // Keep looking in the positions after every legal move
+        for mv in game.legal_moves() {
+            let mut next = game.clone();
+            next.play(mv).expect("legal moves can always be played");
+            stack.push(next);
+        }
+    }
+
+    // Easiest first
+    puzzles.sort_by_key(|puzzle| puzzle.difficulty);
+    Ok(puzzles)
+}
+
+// Checks an answer: whether the move still wins within the `plies` moves (by either player) left
+pub fn is_winning_answer(game: &Game, mv: Move, plies: usize, solver: &mut Solver) -> Result<bool, SolveError> {
+    let outcome = solver.evaluate_moves(game)?.into_iter()
+        .find(|&(legal, _)| legal == mv)
+        .map(|(_, outcome)| outcome);
+    Ok(matches!(outcome, Some(Outcome::Win(length)) if length <= plies))
+}
+
+// Works out the solution line and difficulty of a puzzle position
+fn new_puzzle(game: &Game, moves: usize, first: Move, solver: &mut Solver) -> Result<Puzzle, SolveError> {
+    // Play out the main line: our fastest win against the opponent's longest defence
+    let mut solution = vec![first];
+    let mut line = game.clone();
+    line.play(first).expect("legal moves can always be played");
+    while !line.is_finished() {
+        let (mv, _) = solver.best_move(&line)?;
+        solution.push(mv);
+        line.play(mv).expect("legal moves can always be played");
+    }
+
+    // One more difficulty point for every doubling of the defences to answer
+    let lines = count_lines(game, Some(first), solver)?;
+    let doublings = usize::BITS - (lines - 1).leading_zeros();
+    let difficulty = (1 + doublings).min(MAX_DIFFICULTY);
+
+    Ok(Puzzle {game: game.clone(), moves, solution, lines, difficulty})
+}
+
+// Counts the leaves of the solution tree: we play our fastest win (or `first`),
+// and every reply the opponent has starts a new line
+fn count_lines(game: &Game, first: Option<Move>, solver: &mut Solver) -> Result<usize, SolveError> {
+    let ours = match first {
+        Some(mv) => mv,
+        None => solver.best_move(game)?.0,
+    };
+    let mut after_ours = game.clone();
+    after_ours.play(ours).expect("legal moves can always be played");
+    if after_ours.is_finished() {
+        return Ok(1);
+    }
+
+    let mut lines = 0;
+    for reply in after_ours.legal_moves() {
+        let mut after_reply = after_ours.clone();
+        after_reply.play(reply).expect("legal moves can always be played");
+        lines += match after_reply.is_finished() {
+            true => 1,
+            false => count_lines(&after_reply, None, solver)?,
+        };
+    }
+    Ok(lines)
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tic_tac_toe::game::GameBuilder;
+
+    #[test]
+    fn every_puzzle_has_exactly_one_winning_answer() {
+        let mut solver = Solver::new();
+        for moves in 1..=2 {
+            let puzzles = generate(&Game::new(), moves, &mut solver).unwrap();
+            assert!(!puzzles.is_empty(), "no win in {} puzzles", moves);
+
+            for puzzle in &puzzles {
+                let plies = moves * 2 - 1;
+                let answer = puzzle.solution[0];
+                assert!(is_winning_answer(&puzzle.game, answer, plies, &mut solver).unwrap());
+
+                // Every other move is wrong, even one that wins more slowly
+                for mv in puzzle.game.legal_moves().into_iter().filter(|&mv| mv != answer) {
+                    assert!(!is_winning_answer(&puzzle.game, mv, plies, &mut solver).unwrap(), "{}", puzzle.game.to_board_string());
+                }
+
+                // The solution line ends in the solving player's win
+                let mut line = puzzle.game.clone();
+                for &mv in &puzzle.solution {
+                    line.play(mv).unwrap();
+                }
+                assert!(line.is_finished());
+                assert_eq!(puzzle.solution.len(), plies);
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_answers_are_rejected() {
+        // x wins on 1C, anything else lets o win on the middle row
+        let game = GameBuilder::new().board_string("xx./oo./x.o x").unwrap().build().unwrap();
+        let mut solver = Solver::new();
+        let mut answer = |row, col| is_winning_answer(&game, Move {row, col, piece: game.current_piece()}, 1, &mut solver).unwrap();
+        assert!(answer(0, 2));
+        assert!(!answer(1, 2));
+        assert!(!answer(2, 1));
+        assert!(!answer(0, 0), "taken tiles never win");
+    }
+
+    #[test]
+    fn difficulty_goes_up_with_the_lines_to_answer() {
+        let puzzles = generate(&Game::new(), 2, &mut Solver::new()).unwrap();
+        for pair in puzzles.windows(2) {
+            assert!(pair[0].difficulty <= pair[1].difficulty, "easiest first");
+        }
+        for puzzle in &puzzles {
+            assert!((1..=MAX_DIFFICULTY).contains(&puzzle.difficulty));
+            for other in &puzzles {
+                if puzzle.lines < other.lines {
+                    assert!(puzzle.difficulty <= other.difficulty, "{} lines rated above {} lines", puzzle.lines, other.lines);
+                }
+            }
+        }
+
+        // Winning straight away leaves nothing to answer
+        let puzzles = generate(&Game::new(), 1, &mut Solver::new()).unwrap();
+        assert!(puzzles.iter().all(|puzzle| (puzzle.lines, puzzle.difficulty) == (1, 1)));
+    }
+}
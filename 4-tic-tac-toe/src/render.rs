@@ -0,0 +1,91 @@
+// Draws boards as text, the way the game shows them to players:
+//
+//      A B C
+//    1 x ▢ ▢
+//    2 ▢ o ▢
+//    3 ▢ ▢ ▢
+//
+// or describes them in words for screen readers, one row per line: "Row 1: X, empty, empty"
+
+// Standard Library Import Statements
+use std::fmt::Write;
+
+// Package Import Statements
+use crate::game::{Game, Players, Tiles};
+use crate::notation::tile_name;
+
+/// Symbol drawn on empty tiles
+pub const EMPTY_TILE: char = '\u{25A2}';
+
+/// Draws a game's board, with column letters above it and row numbers down the side
+pub fn render_game(game: &Game) -> String {
+    render_tiles(game.tiles(), game.players())
+}
+
+/// Draws a board with the players' symbols, ending with a blank line
+pub fn render_tiles(tiles: &Tiles, players: &Players) -> String {
+    let mut out = String::from("  ");
+
+    // Col headers
+    for j in 0..tiles.first().map_or(0, Vec::len) as u8 {
+        write!(out, " {}", (b'A' + j) as char).expect("writing to a String can't fail");
+    }
+    out.push('\n');
+
+    for (i, row) in tiles.iter().enumerate() {
+        // Row headers
+        write!(out, "{:>2}", i + 1).expect("writing to a String can't fail");
+
+        // Read actual enum values from the row
+        for tile in row {
+            out.push(' ');
+            out.push(match *tile {
+                Some(piece) => players.symbol(piece),
+                None => EMPTY_TILE,
+            });
+        }
+        out.push('\n');
+    }
+
+    out.push('\n');
+    out
+}
+
+/// Describes a board in words, one row per line, e.g. "Row 2: empty, O, empty"
+///
+/// ```
+/// use tic_tac_toe::{describe_tiles, Game};
+///
+/// let mut game = Game::new();
+/// game.make_move(0, 0).unwrap();
+/// game.make_move(1, 1).unwrap();
+/// assert_eq!(describe_tiles(game.tiles(), game.players()), "Row 1: X, empty, empty\nRow 2: empty, O, empty\nRow 3: empty, empty, empty\n");
+/// ```
+pub fn describe_tiles(tiles: &Tiles, players: &Players) -> String {
+    describe_tiles_with(tiles, players, "empty", |number, tiles| format!("Row {}: {}", number, tiles))
+}
+
+/// Describes a board in other words: `empty` stands for an empty tile and `row` writes out a row
+/// from its number and its tiles joined with commas
+pub fn describe_tiles_with<F: Fn(usize, &str) -> String>(tiles: &Tiles, players: &Players, empty: &str, row: F) -> String {
+    let mut out = String::new();
+    for (i, cells) in tiles.iter().enumerate() {
+        let cells: Vec<String> = cells.iter().map(|tile| match *tile {
+            Some(piece) => players.symbol(piece).to_uppercase().to_string(),
+            None => empty.to_string(),
+        }).collect();
+        writeln!(out, "{}", row(i + 1, &cells.join(", "))).expect("writing to a String can't fail");
+    }
+    out
+}
+
+/// Names a tile in words, e.g. "row 2, column B"
+pub fn describe_tile(row: usize, col: usize) -> String {
+    describe_tile_with(row, col, |row, col| format!("row {}, column {}", row, col))
+}
+
+/// Names a tile in other words, given its row number and column letter
+pub fn describe_tile_with<F: Fn(usize, &str) -> String>(row: usize, col: usize, name: F) -> String {
+    let tile = tile_name(row, col);
+    name(row + 1, &tile[tile.len() - 1..])
+}
@@ -0,0 +1,132 @@
+// Keeps score over a best-of-N match
+
+// Standard Library Import Statements
+use std::fmt;
+
+// Package Import Statements
+use crate::i18n::t;
+
+// Define the Scoreboard
+#[derive(Debug, Clone)]
+pub struct Scoreboard {
+    names: Vec<String>, // One per seat, e.g. "Alice (x)"
+    wins: Vec<usize>,   // Wins for each seat
+    ties: usize,
+    best_of: usize,
+}
+
+impl Scoreboard {
+    // Constructor for a match of at most `best_of` games
+    pub fn new(names: Vec<String>, best_of: usize) -> Self {
+        let wins = vec![0; names.len()];
+        Self {names, wins, ties: 0, best_of}
+    }
+
+    // Records a finished game, `None` for a tie
+    pub fn record(&mut self, winner: Option<usize>) {
+        match winner {
+            Some(seat) => self.wins[seat] += 1,
+            None => self.ties += 1,
+        }
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.wins.iter().sum::<usize>() + self.ties
+    }
+
+    // The match is over once every game is played or the leader can't be caught
+    pub fn is_decided(&self) -> bool {
+        let remaining = self.best_of.saturating_sub(self.games_played());
+        let mut sorted = self.wins.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        remaining == 0 || sorted[0] > sorted[1] + remaining
+    }
+
+    // The seat with the most wins, if nobody is level with them
+    pub fn leader(&self) -> Option<usize> {
+        let most = *self.wins.iter().max()?;
+        match self.wins.iter().filter(|&&wins| wins == most).count() {
+            1 => self.wins.iter().position(|&wins| wins == most),
+            _ => None,
+        }
+    }
+
+    // One line running score, e.g. "Alice (x) 2, Bob (o) 1, ties 1"
+    pub fn summary(&self) -> String {
+        let scores: Vec<String> = self.names.iter().zip(&self.wins)
+            .map(|(name, wins)| format!("{} {}", name, wins))
+            .collect();
+        t!("score.summary", scores = scores.join(", "), ties = self.ties)
+    }
+}
+
+// Impl Display so that the final table can be printed directly
+impl fmt::Display for Scoreboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (player, wins, ties, games) = (t!("score.player"), t!("score.wins"), t!("score.ties"), t!("score.games"));
+        let width = self.names.iter().chain([&player, &ties, &games]).map(|name| name.chars().count()).max().unwrap_or(0);
+        let wins_width = wins.chars().count().max(4);
+
+        writeln!(f, "{:<width$}  {:>wins_width$}", player, wins, width = width, wins_width = wins_width)?;
+        for (name, wins) in self.names.iter().zip(&self.wins) {
+            writeln!(f, "{:<width$}  {:>wins_width$}", name, wins, width = width, wins_width = wins_width)?;
+        }
+        writeln!(f, "{:<width$}  {:>wins_width$}", ties, self.ties, width = width, wins_width = wins_width)?;
+        write!(f, "{:<width$}  {:>wins_width$}", games, self.games_played(), width = width, wins_width = wins_width)
+    }
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scoreboard(seats: usize, best_of: usize) -> Scoreboard {
+        Scoreboard::new((0..seats).map(|seat| format!("Player {}", seat + 1)).collect(), best_of)
+    }
+
+    #[test]
+    fn matches_end_once_the_leader_cant_be_caught() {
+        let mut scores = scoreboard(2, 5);
+        scores.record(Some(0));
+        scores.record(Some(0));
+        assert!(!scores.is_decided());
+        scores.record(Some(0));
+        assert!(scores.is_decided(), "3 wins out of 5 can't be caught");
+        assert_eq!((scores.games_played(), scores.leader()), (3, Some(0)));
+    }
+
+    #[test]
+    fn ties_use_up_games() {
+        let mut scores = scoreboard(2, 3);
+        scores.record(None);
+        scores.record(Some(1));
+        assert!(!scores.is_decided());
+        scores.record(None);
+        assert!(scores.is_decided());
+        assert_eq!(scores.leader(), Some(1));
+        assert_eq!(scores.summary(), t!("score.summary", scores = "Player 1 0, Player 2 1", ties = 2));
+
+        // A level match has no leader
+        let mut scores = scoreboard(2, 2);
+        scores.record(Some(0));
+        scores.record(Some(1));
+        assert!(scores.is_decided());
+        assert_eq!(scores.leader(), None);
+    }
+
+    #[test]
+    fn three_seats_compare_the_top_two() {
+        let mut scores = scoreboard(3, 5);
+        scores.record(Some(2));
+        scores.record(Some(2));
+        scores.record(Some(0));
+        assert!(!scores.is_decided(), "player 1 can still catch up");
+        scores.record(Some(2));
+        assert!(scores.is_decided());
+        assert_eq!(scores.leader(), Some(2));
+        let table = scores.to_string();
+        let games = table.lines().last().unwrap();
+        assert!(games.starts_with(&t!("score.games")) && games.ends_with(" 4"), "{}", table);
+    }
+}
@@ -0,0 +1,571 @@
+// A small HTTP server hosting many games at once, each one known by its id
+//
+//   POST   /games             starts a game. The optional JSON body holds the same settings as the
+//                             command line, e.g. {"size":4,"win":3,"players":3,"bot":"o","place":["2B:x"]}
+//                             or {"time":"30+2"} for a timed game
+//   GET    /games/{id}        the state of a game
+//   POST   /games/{id}/moves  plays a move, e.g. {"move":"2B"} (the computer replies if it is playing)
+//   DELETE /games/{id}        ends a game and forgets it
+//   GET    /games/{id}/events streams the game to a spectator, one JSON line per position: the start,
+//                             every move played so far, then every new move until the game ends
+//
+// Bodies use the same JSON as the --json machine interface. Failed requests answer with an "error"
+// object, and rejected moves also carry the state of the game. In a timed game the clock of the side to
+// move runs between requests, and a side that runs out of time loses the next time anyone asks for the game.
+// Games nobody asks about for an hour are forgotten, finished ones after five minutes.
+
+// Standard Library Import Statements
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Package Import Statements
+use tic_tac_toe::clock::Clocks;
+use tic_tac_toe::game::{Game, MoveError};
+use tic_tac_toe::{move_name, parse_move};
+use serde_json::{json, Map, Value};
+use crate::bot::Bot;
+use crate::i18n::t;
+use crate::machine::{self, error};
+use crate::setup::{bot_seat, current_seat, new_game, parse_args};
+
+// Biggest request body accepted, game settings and moves are far smaller
+const MAX_BODY: usize = 64 * 1024;
+
+// Longest request line or header accepted
+const MAX_LINE: usize = 8 * 1024;
+
+// How long a client may take to send its request, or a spectator to take the next position
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Most connections answered at once, each on its own thread. Spectators count for as long as they watch.
+const MAX_CONNECTIONS: usize = 64;
+
+// How long a game is kept after the last request for it, and a finished game after the last look at it
+const IDLE_EXPIRY: Duration = Duration::from_secs(60 * 60);
+const FINISHED_EXPIRY: Duration = Duration::from_secs(5 * 60);
+
+// A game being played on the server, with the computer opponent if it has one
+struct HostedGame {
+    game: Game,
+    bot: Bot,
+    bot_seat: Option<usize>,
+    clocks: Option<Clocks>,        // Only timed games have clocks
+    spectators: Vec<Sender<Value>>, // Every spectator is sent the position after each move
+    last_used: Instant,             // When a request last asked for the game
+}
+
+impl HostedGame {
+    // Sends the position after the last move to every spectator, forgetting the ones who left
+    fn broadcast(&mut self) {
+        let event = position_event(&self.game, self.clocks.as_ref());
+        self.spectators.retain(|spectator| spectator.send(event.clone()).is_ok());
+    }
+
+    // Ends the game if the side to move has run out of time, letting the spectators know
+    fn check_clock(&mut self) {
+        let game = &mut self.game;
+        if self.clocks.as_mut().is_some_and(|clocks| clocks.check(game)) {
+            self.broadcast();
+        }
+    }
+
+    // Whether the game has gone unused long enough to be forgotten
+    fn is_expired(&self) -> bool {
+        let expiry = if self.game.is_finished() { FINISHED_EXPIRY } else { IDLE_EXPIRY };
+        self.last_used.elapsed() > expiry
+    }
+
+    // The machine interface state of the game, with its id first
+    fn state(&self, id: u64) -> Value {
+        let mut fields = Map::new();
+        fields.insert("id".to_string(), id.into());
+        if let Value::Object(state) = machine::state(&self.game, self.clocks.as_ref()) {
+            fields.extend(state);
+        }
+        Value::Object(fields)
+    }
+}
+
+// Every game on the server. Each game has its own lock, so a slow computer move only holds up its own game.
+#[derive(Default)]
+struct Games {
+    next_id: u64,
+    games: HashMap<u64, Arc<Mutex<HostedGame>>>,
+}
+
+impl Games {
+    // Forgets the games that expired. A game busy with a request is in use, so it stays.
+    fn expire(&mut self) {
+        self.games.retain(|_, game| match game.try_lock() {
+            Ok(hosted) => !hosted.is_expired(),
+            Err(TryLockError::Poisoned(poisoned)) => !poisoned.into_inner().is_expired(),
+            Err(TryLockError::WouldBlock) => true,
+        });
+    }
+}
+
+// An HTTP response: the status and the JSON body, if there is one
+struct Response {
+    status: u16,
+    body: Option<Value>,
+    location: Option<String>, // Where a newly created game can be found
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {status: 200, body: Some(body), location: None}
+    }
+
+    fn error(status: u16, error: Value) -> Self {
+        Self {status, body: Some(json!({"error": error})), location: None}
+    }
+}
+
+// Accepts connections until the listener fails, answering each one on its own thread.
+// Once MAX_CONNECTIONS are open, new ones are turned away until one closes.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let games = Arc::new(Mutex::new(Games::default()));
+    let open = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let connection = match Connection::open(&open) {
+            Some(connection) => connection,
+            None => {
+                // A client hanging up early is its own problem, the server carries on
+                let _ = stream.set_write_timeout(Some(TIMEOUT)).and_then(|_| write_response(&mut stream, &server_busy()));
+                continue;
+            },
+        };
+        let games = Arc::clone(&games);
+        thread::spawn(move || {
+            let _ = handle_connection(stream, &games);
+            drop(connection);
+        });
+    }
+    Ok(())
+}
+
+// A connection being answered, counted until it is dropped
+struct Connection {
+    open: Arc<AtomicUsize>,
+}
+
+impl Connection {
+    // Counts a new connection, None if MAX_CONNECTIONS are already open
+    fn open(open: &Arc<AtomicUsize>) -> Option<Self> {
+        open.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| (count < MAX_CONNECTIONS).then_some(count + 1)).ok()?;
+        Some(Self {open: Arc::clone(open)})
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.open.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Reads one request, answers it and closes the connection
+fn handle_connection(mut stream: TcpStream, games: &Mutex<Games>) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let (method, path, body) = match read_request(&mut BufReader::new(&stream)) {
+        Ok(request) => request,
+        Err(response) => return write_response(&mut stream, &response),
+    };
+
+    // Spectators keep the connection open for the rest of the game
+    if let ("GET", ["games", game_id, "events"]) = (method.as_str(), &path_segments(&path)[..]) {
+        if let Ok(id) = game_id.parse() {
+            return stream_events(stream, id, games);
+        }
+    }
+    write_response(&mut stream, &route(&method, &path, &body, games))
+}
+
+// GET /games/{id}/events: catches the spectator up on the game so far, then sends every move as it happens
+fn stream_events(mut stream: TcpStream, id: u64, games: &Mutex<Games>) -> io::Result<()> {
+    let game = match lock(games).games.get(&id) {
+        Some(game) => Arc::clone(game),
+        None => return write_response(&mut stream, &game_not_found()),
+    };
+
+    // Only the game keeps the sender, so deleting the game ends the stream
+    let (sender, receiver) = mpsc::channel();
+    let catch_up = {
+        let mut hosted = lock(&game);
+        hosted.spectators.push(sender);
+        replay(&hosted.game)
+    };
+    drop(game);
+
+    // No Content-Length: the positions keep coming until the game ends or is deleted
+    stream.write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Type: application/x-ndjson\r\n\r\n")?;
+    for event in catch_up.into_iter().chain(receiver) {
+        writeln!(stream, "{}", event)?;
+        stream.flush()?;
+        if event.get("winner").is_some_and(|winner| !winner.is_null()) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Every position of a game so far, starting from before the first move
+fn replay(game: &Game) -> Vec<Value> {
+    let mut replay = game.clone();
+    while replay.undo().is_some() {}
+
+    let mut events = vec![position_event(&replay, None)];
+    for &mv in game.history() {
+        replay.play(mv).expect("the moves were legal the first time");
+        events.push(position_event(&replay, None));
+    }
+    events
+}
+
+// A spectator's view of a position: the move that led to it, numbered from 1, and the state of the game.
+// Positions from before the spectator joined are sent without clocks, the times back then aren't kept.
+fn position_event(game: &Game, clocks: Option<&Clocks>) -> Value {
+    let last = game.history().last().map(|&mv| move_name(game, mv));
+    let mut fields = Map::new();
+    fields.insert("number".to_string(), game.history().len().into());
+    fields.insert("move".to_string(), last.into());
+    if let Value::Object(state) = machine::state(game, clocks) {
+        fields.extend(state);
+    }
+    Value::Object(fields)
+}
+
+// Reads the request line, headers and body. Malformed requests get an error response back.
+fn read_request<R: BufRead>(reader: &mut R) -> Result<(String, String, String), Response> {
+    let bad_request = |message: &str| Response::error(400, error("BadRequest", vec![("message", message.into())]));
+
+    let request_line = read_line(reader).ok_or_else(|| bad_request("could not read the request line"))?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/1.") => (method, path),
+        _ => return Err(bad_request("invalid request line")),
+    };
+
+    // Only the body length matters to us
+    let mut length = 0;
+    loop {
+        let header = read_line(reader).ok_or_else(|| bad_request("could not read the headers"))?;
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| bad_request("invalid Content-Length"))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Response::error(413, error("BodyTooLarge", vec![("max_bytes", MAX_BODY.into())])));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|_| bad_request("the body is shorter than its Content-Length"))?;
+    let body = String::from_utf8(body).map_err(|_| bad_request("the body is not valid UTF-8"))?;
+    Ok((method.to_string(), path.to_string(), body))
+}
+
+// Reads a line without its line ending, None if it is missing or too long
+fn read_line<R: BufRead>(reader: &mut R) -> Option<String> {
+    let mut line = String::new();
+    match reader.take(MAX_LINE as u64).read_line(&mut line) {
+        Ok(_) if line.ends_with('\n') => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+        _ => None,
+    }
+}
+
+// Sends a response with a JSON body
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = response.body.as_ref().map_or(String::new(), |body| format!("{}\n", body));
+
+    let mut head = format!("HTTP/1.1 {} {}\r\nConnection: close\r\nContent-Length: {}\r\n", response.status, reason, body.len());
+    if response.body.is_some() {
+        head += "Content-Type: application/json\r\n";
+    }
+    if let Some(location) = &response.location {
+        head += &format!("Location: {}\r\n", location);
+    }
+    stream.write_all(format!("{}\r\n{}", head, body).as_bytes())?;
+    stream.flush()
+}
+
+// Picks the endpoint for a request
+fn route(method: &str, path: &str, body: &str, games: &Mutex<Games>) -> Response {
+    let segments = path_segments(path);
+    let not_found = || Response::error(404, error("NotFound", vec![("path", path.into())]));
+    let id = |segment: &str| segment.parse::<u64>().ok();
+
+    match (method, &segments[..]) {
+        ("POST", ["games"]) => create_game(body, games),
+        ("GET", ["games", game_id]) => match id(game_id) {
+            Some(id) => with_game(games, id, |hosted| Response::ok(hosted.state(id))),
+            None => not_found(),
+        },
+        ("DELETE", ["games", game_id]) => match id(game_id).and_then(|id| lock(games).games.remove(&id)) {
+            Some(_) => Response {status: 204, body: None, location: None},
+            None => game_not_found(),
+        },
+        ("POST", ["games", game_id, "moves"]) => match id(game_id) {
+            Some(id) => with_game(games, id, |hosted| play_move(id, hosted, body)),
+            None => not_found(),
+        },
+        ("GET", ["games", _, "events"]) => not_found(), // Streams for real game ids are answered before routing
+        (_, ["games"]) | (_, ["games", _]) | (_, ["games", _, "moves"]) | (_, ["games", _, "events"]) => {
+            Response::error(405, error("MethodNotAllowed", vec![("method", method.into())]))
+        },
+        _ => not_found(),
+    }
+}
+
+// POST /games: sets up a game from the settings in the body
+fn create_game(body: &str, games: &Mutex<Games>) -> Response {
+    let invalid = |message: String| Response::error(400, error("InvalidSetup", vec![("message", message.into())]));
+    let settings = match body.trim() {
+        "" => Value::Object(Map::new()),
+        body => match serde_json::from_str(body) {
+            Ok(settings) => settings,
+            Err(err) => return Response::error(400, error("InvalidJson", vec![("message", err.to_string().into())])),
+        },
+    };
+
+    // The settings become command line arguments, so they are checked exactly like the real ones
+    let options = match setup_args(&settings).and_then(|args| parse_args(args.into_iter())) {
+        Ok(options) => options,
+        Err(message) => return invalid(message),
+    };
+    let game = match new_game(&options) {
+        Ok(game) => game,
+        Err(message) => return invalid(t!("setup.failed", error = message)),
+    };
+    let bot_seat = match options.bot.as_ref().map(|bot| bot_seat(&game, bot)).transpose() {
+        Ok(bot_seat) => bot_seat,
+        Err(message) => return invalid(message),
+    };
+
+    // A computer player that can't search this board would hold the game's lock for nothing, so refuse it now
+    let mut bot = Bot::new();
+    if bot_seat.is_some() {
+        if let Err(err) = bot.solver().check(&game) {
+            return Response::error(400, error("BotUnsupported", vec![("message", err.to_string().into())]));
+        }
+    }
+
+    // The computer may have the first move, and its clock is already running if the game is timed
+    let clocks = options.time.map(|control| Clocks::for_game(control, &game));
+    let mut hosted = HostedGame {game, bot, bot_seat, clocks, spectators: Vec::new(), last_used: Instant::now()};
+    if let Err(err) = bot_reply(&mut hosted) {
+        return Response::error(500, err);
+    }
+
+    let mut games = lock(games);
+    games.expire();
+    games.next_id += 1;
+    let id = games.next_id;
+    let body = hosted.state(id);
+    games.games.insert(id, Arc::new(Mutex::new(hosted)));
+    Response {status: 201, body: Some(body), location: Some(format!("/games/{}", id))}
+}
+
+// Turns game settings into the command line arguments that mean the same thing
+fn setup_args(settings: &Value) -> Result<Vec<String>, String> {
+    let fields = match settings {
+        Value::Object(fields) => fields,
+        _ => return Err("Expected a JSON object of game settings".to_string()),
+    };
+
+    let mut args = Vec::new();
+    for (key, value) in fields {
+        let flag = format!("--{}", key.replace('_', "-"));
+        match (key.as_str(), value) {
+            ("order_chaos", Value::Bool(true)) => args.push(flag),
+            ("order_chaos", Value::Bool(false)) => {},
+            ("size", Value::Number(_)) | ("win", Value::Number(_)) | ("players", Value::Number(_)) | ("time", Value::Number(_)) => {
+                args.extend(vec![flag, value.to_string()]);
+            },
+            ("symbols", Value::String(text)) | ("turn_order", Value::String(text))
+            | ("first", Value::String(text)) | ("bot", Value::String(text)) | ("time", Value::String(text)) => {
+                args.extend(vec![flag, text.clone()]);
+            },
+            ("place", Value::Array(placements)) => for placement in placements {
+                let placement = placement.as_str().ok_or("Every entry of \"place\" must be a string, e.g. \"2B:x\"")?;
+                args.extend(vec![flag.clone(), placement.to_string()]);
+            },
+            _ => return Err(format!("Invalid game setting: \"{}\"", key)),
+        }
+    }
+    Ok(args)
+}
+
+// POST /games/{id}/moves: plays the move in the body, then lets the computer reply
+fn play_move(id: u64, hosted: &mut HostedGame, body: &str) -> Response {
+    let invalid = |kind: &str, details| Response::error(400, error(kind, details));
+    let command: Value = match serde_json::from_str(body) {
+        Ok(command) => command,
+        Err(err) => return invalid("InvalidJson", vec![("message", err.to_string().into())]),
+    };
+    let input = match command.get("move").and_then(Value::as_str) {
+        Some(input) => input,
+        None => return invalid("InvalidCommand", vec![("message", "expected {\"move\":\"2B\"}".into())]),
+    };
+
+    let game = &mut hosted.game;
+    let (row, col, piece) = match parse_move(input, game.tiles().len(), game.players()) {
+        Ok(parsed) => parsed,
+        Err(err) => return invalid("InvalidMove", vec![("input", input.into()), ("message", err.reason.to_string().into())]),
+    };
+    let result = match piece {
+        Some(piece) => game.make_move_with(row, col, piece),
+        None => game.make_move(row, col),
+    };
+
+    // Rejected moves come back with the game, so the client can see why
+    if let Err(err) = result {
+        let error = machine::move_error(game.players(), &err);
+        let mut body = hosted.state(id);
+        if let Value::Object(fields) = &mut body {
+            fields.insert("error".to_string(), error);
+        }
+        return Response {status: move_error_status(&err), body: Some(body), location: None};
+    }
+    if let Some(clocks) = hosted.clocks.as_mut() {
+        clocks.switch(&hosted.game);
+    }
+    hosted.broadcast();
+
+    match bot_reply(hosted) {
+        Ok(()) => Response::ok(hosted.state(id)),
+        Err(err) => Response::error(500, err),
+    }
+}
+
+// Moves that come too late or go on a taken tile conflict with the game, the rest can never be played
+fn move_error_status(err: &MoveError) -> u16 {
+    match err {
+        MoveError::GameAlreadyOver | MoveError::TileNotEmpty {..} => 409,
+        _ => 422,
+    }
+}
+
+// Lets the computer move if it is its turn. Its clock runs while it thinks, like everyone else's.
+fn bot_reply(hosted: &mut HostedGame) -> Result<(), Value> {
+    let game = &mut hosted.game;
+    if game.is_finished() || hosted.bot_seat.is_none() || hosted.bot_seat != Some(current_seat(game, 0)) {
+        return Ok(());
+    }
+    let bot_failed = |message: String| error("BotFailed", vec![("message", message.into())]);
+    let (mv, _) = hosted.bot.choose_move(game).map_err(|err| bot_failed(err.to_string()))?;
+    if hosted.clocks.as_mut().is_some_and(|clocks| clocks.check(game)) {
+        hosted.broadcast();
+        return Ok(());
+    }
+    game.play(mv).map_err(|err| bot_failed(err.with_symbols(game.players()).to_string()))?;
+    if let Some(clocks) = hosted.clocks.as_mut() {
+        clocks.switch(&hosted.game);
+    }
+    hosted.broadcast();
+    Ok(())
+}
+
+// Runs a request against one game, holding only that game's lock while it does
+fn with_game<F: FnOnce(&mut HostedGame) -> Response>(games: &Mutex<Games>, id: u64, handle: F) -> Response {
+    let game = match lock(games).games.get(&id) {
+        Some(game) => Arc::clone(game),
+        None => return game_not_found(),
+    };
+    let mut hosted = lock(&game);
+    hosted.last_used = Instant::now();
+    hosted.check_clock();
+    handle(&mut hosted)
+}
+
+// The parts of a path between the slashes, e.g. ["games", "1", "moves"]
+fn path_segments(path: &str) -> Vec<&str> {
+    path.trim_end_matches('/').split('/').skip(1).collect()
+}
+
+fn game_not_found() -> Response {
+    Response::error(404, error("GameNotFound", vec![]))
+}
+
+fn server_busy() -> Response {
+    Response::error(503, error("ServerBusy", vec![("max_connections", MAX_CONNECTIONS.into())]))
+}
+
+// Locks a mutex, carrying on even if a thread panicked while holding it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A game hosted on the server, last asked for `ago`
+    fn hosted(game: Game, ago: Duration) -> Arc<Mutex<HostedGame>> {
+        let last_used = Instant::now().checked_sub(ago).unwrap();
+        Arc::new(Mutex::new(HostedGame {game, bot: Bot::new(), bot_seat: None, clocks: None, spectators: Vec::new(), last_used}))
+    }
+
+    #[test]
+    fn unused_games_are_forgotten() {
+        let mut finished = Game::new();
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            finished.make_move(row, col).unwrap();
+        }
+        let minutes = |count: u64| Duration::from_secs(count * 60);
+
+        let mut games = Games::default();
+        games.games.insert(1, hosted(Game::new(), minutes(10)));
+        games.games.insert(2, hosted(Game::new(), minutes(61)));
+        games.games.insert(3, hosted(finished.clone(), minutes(1)));
+        games.games.insert(4, hosted(finished, minutes(6)));
+
+        // A game in the middle of a request is still in use, however old
+        games.games.insert(5, hosted(Game::new(), minutes(61)));
+        let busy = Arc::clone(&games.games[&5]);
+        let _request = busy.lock().unwrap();
+
+        games.expire();
+        let mut kept: Vec<u64> = games.games.keys().copied().collect();
+        kept.sort();
+        assert_eq!(kept, [1, 3, 5]);
+    }
+
+    #[test]
+    fn connections_are_limited() {
+        let open = Arc::new(AtomicUsize::new(0));
+        let connections: Vec<Connection> = (0..MAX_CONNECTIONS).map(|_| Connection::open(&open).unwrap()).collect();
+        assert!(Connection::open(&open).is_none());
+
+        // Closing one makes room for the next
+        drop(connections);
+        assert_eq!(open.load(Ordering::SeqCst), 0);
+        assert!(Connection::open(&open).is_some());
+    }
+}
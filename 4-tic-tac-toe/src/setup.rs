@@ -0,0 +1,189 @@
+// Setting up games from the command line, and the seats players sit in. Shared by the interactive game
+// and everything that sets up games of its own: matches, correspondence games and the server.
+
+// Package Import Statements
+use tic_tac_toe::clock::TimeControl;
+use tic_tac_toe::game::{Game, GameBuilder, Piece, Players, Role, SetupError, Variant, Winner};
+use tic_tac_toe::parse_move;
+use crate::i18n::t;
+use crate::{accessible, messages, profiles};
+
+// Settings collected from the command line
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub order_chaos: bool,              // --order-chaos: 6x6, either player may place x or o
+    pub size: Option<usize>,            // --size 6: width and height of the board
+    pub win_length: Option<usize>,      // --win 4: pieces in a row needed to win
+    pub player_count: Option<usize>,    // --players 3: number of players using the default symbols
+    pub symbols: Option<Vec<char>>,     // --symbols xo+: one symbol per player
+    pub turn_order: Option<Vec<char>>,  // --turn-order o+x: symbols in the order they move
+    pub first: Option<char>,            // --first o: symbol of the player who moves first
+    pub placements: Vec<String>,        // --place 2B:x (repeatable): pieces already on the board
+    pub coach: bool,                    // --coach: warn before a move that throws away a win or draw
+    pub numpad: bool,                   // --numpad: a single key from 1 to 9 picks a tile, laid out like a numpad
+    pub bot: Option<String>,            // --bot o: the computer plays this symbol (or "order" / "chaos")
+    pub book: Option<String>,           // --book opening.book: opening book the computer checks before searching
+    pub best_of: Option<usize>,         // --best-of 5: play a match of up to this many games
+    pub names: Option<Vec<String>>,     // --names Alice,Bob: player names, in seat order, for profiles
+    pub time: Option<TimeControl>,      // --time 30+2: 30 seconds per player plus 2 more after every move
+    pub json: bool,                     // --json: read JSON commands and write JSON replies instead of prompts
+    pub spectate: Option<String>,       // --spectate 127.0.0.1:8080/games/1: watch a game hosted by `serve`
+    pub log: Option<String>,            // --log events.jsonl: append every move, rejected move and result to a file
+}
+
+// Collects the options from the command line arguments
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+
+    while let Some(arg) = args.next() {
+        // Every flag except --order-chaos, --coach, --numpad, --accessible and --json takes a value
+        let mut value = |flag: &str| args.next().ok_or_else(|| t!("args.missing", flag = flag));
+        let number = |flag: &str, value: String| {
+            value.parse::<usize>().map_err(|_| t!("args.number", flag = flag, value = value))
+        };
+
+        match arg.trim() {
+            "--order-chaos" => options.order_chaos = true,
+            "--coach" => options.coach = true,
+            "--numpad" => options.numpad = true,
+            "--accessible" => accessible::enable(), // Changes how everything is printed, not the game
+            "--json" => options.json = true,
+            "--size" => options.size = Some(number("--size", value("--size")?)?),
+            "--win" => options.win_length = Some(number("--win", value("--win")?)?),
+            "--players" => options.player_count = Some(number("--players", value("--players")?)?),
+            "--symbols" => options.symbols = Some(value("--symbols")?.chars().collect()),
+            "--turn-order" => options.turn_order = Some(value("--turn-order")?.chars().collect()),
+            "--first" => {
+                let first = value("--first")?;
+                let mut chars = first.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(symbol), None) => options.first = Some(symbol),
+                    _ => return Err(t!("args.first", value = first)),
+                }
+            },
+            "--place" => options.placements.push(value("--place")?),
+            "--bot" => options.bot = Some(value("--bot")?.to_lowercase()),
+            "--book" => options.book = Some(value("--book")?),
+            "--log" => options.log = Some(value("--log")?),
+            "--spectate" => options.spectate = Some(value("--spectate")?),
+            "--time" => options.time = Some(TimeControl::parse(&value("--time")?)
+                .ok_or_else(|| t!("args.time"))?),
+            "--names" => options.names = Some(parse_names(&value("--names")?)?),
+            "--best-of" => options.best_of = Some(number("--best-of", value("--best-of")?)?.max(1)),
+            other => return Err(t!("args.unknown", argument = other)),
+        }
+    }
+
+    Ok(options)
+}
+
+// Splits --names into player names, which have to be there and be different to tell the players' profiles apart
+pub fn parse_names(value: &str) -> Result<Vec<String>, String> {
+    let names: Vec<String> = value.split(',').map(profiles::clean_name).collect();
+    if names.iter().enumerate().any(|(seat, name)| !is_new_name(&names[..seat], name)) {
+        return Err(t!("args.names_different"));
+    }
+    Ok(names)
+}
+
+// Checks a player's name can be told apart from the names before it: profiles are looked up by name
+pub fn is_new_name(names: &[String], name: &str) -> bool {
+    !name.is_empty() && !names.iter().any(|other| other == name)
+}
+
+// Sets up a new game from the command line options
+pub fn new_game(options: &Options) -> Result<Game, String> {
+    game_builder(options)?.build().map_err(|err| messages::setup_error(&err))
+}
+
+// Collects the game setup from the command line options
+pub fn game_builder(options: &Options) -> Result<GameBuilder, String> {
+    let variant = if options.order_chaos { Variant::OrderAndChaos } else { Variant::Standard };
+    let default = Game::with_variant(variant);
+    let mut builder = GameBuilder::new().variant(variant);
+    let setup_error = |err: SetupError| messages::setup_error(&err);
+
+    if let Some(size) = options.size {
+        builder = builder.size(size);
+    }
+    if let Some(win_length) = options.win_length {
+        builder = builder.win_length(win_length);
+    }
+
+    // Choose the players and the order they move in
+    let mut players = match (&options.symbols, options.player_count) {
+        (Some(symbols), _) => Players::with_symbols(symbols.clone()).map_err(setup_error)?,
+        (None, Some(count)) => Players::new(count).map_err(setup_error)?,
+        (None, None) => default.players().clone(),
+    };
+    let piece_for = |players: &Players, symbol: char| {
+        players.piece_for(symbol).ok_or_else(|| t!("setup.symbol", symbol = symbol))
+    };
+    if let Some(order) = &options.turn_order {
+        let order = order.iter()
+            .map(|&symbol| piece_for(&players, symbol))
+            .collect::<Result<_, _>>()?;
+        players = players.with_turn_order(order).map_err(setup_error)?;
+    }
+    if let Some(first) = options.first {
+        builder = builder.starting_piece(piece_for(&players, first)?);
+    }
+
+    // Handicap pieces use the same notation as moves, e.g. "2B:x"
+    let size = options.size.unwrap_or(default.tiles().len());
+    for placement in &options.placements {
+        match parse_move(&placement.replacen(':', " ", 1), size, &players) {
+            Ok((row, col, Some(piece))) => builder = builder.place(row, col, piece),
+            _ => return Err(t!("setup.placement", placement = placement)),
+        }
+    }
+
+    Ok(builder.players(players))
+}
+
+// Explains why the game could not be set up
+pub fn setup_error(message: String) -> String {
+    t!("setup.failed", error = message)
+}
+
+// Finds the seat of the player to move in the `game_number`th game of a match (counting from 0)
+pub fn current_seat(game: &Game, game_number: usize) -> usize {
+    match game.current_role() {
+        Some(Role::Order) => game_number % 2,
+        Some(Role::Chaos) => (game_number + 1) % 2,
+        None => game.current_piece().index(),
+    }
+}
+
+// Finds the seat of the winner, None for a tie
+pub fn winning_seat(winner: Winner, game_number: usize) -> Option<usize> {
+    match winner {
+        Winner::Player(piece) => Some(piece.index()),
+        Winner::Order => Some(game_number % 2),
+        Winner::Chaos => Some((game_number + 1) % 2),
+        Winner::Tie => None,
+    }
+}
+
+// Finds the seat the computer plays from --bot: a symbol, or "order" / "chaos" for the first game
+pub fn bot_seat(game: &Game, bot: &str) -> Result<usize, String> {
+    let mut chars = bot.chars();
+    let seat = match (game.variant(), bot) {
+        (Variant::OrderAndChaos, "order") => Some(0),
+        (Variant::OrderAndChaos, "chaos") => Some(1),
+        (Variant::OrderAndChaos, _) => None,
+        (Variant::Standard, _) => match (chars.next(), chars.next()) {
+            (Some(symbol), None) => game.players().piece_for(symbol).map(Piece::index),
+            _ => None,
+        },
+    };
+    seat.ok_or_else(|| t!("args.bot", bot = bot))
+}
+
+// Counts the seats at the board: one per player, or the two roles of Order and Chaos
+pub fn seat_count(game: &Game) -> usize {
+    match game.variant() {
+        Variant::Standard => game.players().count(),
+        Variant::OrderAndChaos => 2,
+    }
+}
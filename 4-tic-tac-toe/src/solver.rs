@@ -0,0 +1,247 @@
+// Finds the best move in a position by trying every possible game from it
+
+// Standard Library Import Statements
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Package Import Statements
+use tic_tac_toe::game::{Game, Move, Piece, Role, Winner};
+use crate::i18n::t;
+
+// Stop searching once this many positions have been remembered
+const DEFAULT_MAX_POSITIONS: usize = 2_000_000;
+
+// Stop searching after this long, so a hint or computer move never keeps the player waiting
+const DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(5);
+
+// The clock is checked once every this many new positions
+const CLOCK_CHECK_INTERVAL: usize = 1024;
+
+// The theoretical result of a position for the player about to move, with perfect play from both sides
+// The number is how many moves (by either player) until the game ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win(usize),
+    Draw,
+    Loss(usize),
+}
+
+impl Outcome {
+    // The same result seen by the other player one move earlier
+    pub fn flip(self) -> Outcome {
+        match self {
+            Outcome::Win(moves) => Outcome::Loss(moves + 1),
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss(moves) => Outcome::Win(moves + 1),
+        }
+    }
+
+    // Higher is better: quick wins first, then draws, then slow losses
+    pub fn score(self) -> i64 {
+        match self {
+            Outcome::Win(moves) => 1_000_000 - moves as i64,
+            Outcome::Draw => 0,
+            Outcome::Loss(moves) => -1_000_000 + moves as i64,
+        }
+    }
+
+    // Compares only win/draw/loss, ignoring how long it takes
+    pub fn rank(self) -> i8 {
+        match self {
+            Outcome::Win(_) => 1,
+            Outcome::Draw => 0,
+            Outcome::Loss(_) => -1,
+        }
+    }
+}
+
+// Define reasons a position couldn't be solved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    GameAlreadyOver, // There is nothing left to play
+    UnsupportedPlayers { count: usize }, // Win and loss only make sense for two sides
+    TooLarge { max_positions: usize }, // Gave up before searching every position
+    TooManyEmptyTiles { empty: usize, max: usize }, // Too many games could follow to even start searching
+    TimedOut { limit: Duration }, // Gave up after searching for too long
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::GameAlreadyOver => write!(f, "{}", t!("solve_error.game_over")),
+            SolveError::UnsupportedPlayers {count} => write!(f, "{}", t!("solve_error.players", count = count)),
+            SolveError::TooLarge {max_positions} => write!(f, "{}", t!("solve_error.too_large", max = max_positions)),
+            SolveError::TooManyEmptyTiles {empty, max} => write!(f, "{}", t!("solve_error.empty_tiles", empty = empty, max = max)),
+            SolveError::TimedOut {limit} => write!(f, "{}", t!("solve_error.timed_out", seconds = limit.as_secs())),
+        }
+    }
+}
+
+// Searches positions, remembering the ones it has already solved
+#[derive(Debug, Clone)]
+pub struct Solver {
+    memo: HashMap<Vec<u8>, Outcome>,
+    max_positions: usize,
+    time_limit: Duration,
+    deadline: Option<Instant>, // When the current search gives up
+}
+
+impl Solver {
+    // Constructor
+    pub fn new() -> Self {
+        Self::with_limit(DEFAULT_MAX_POSITIONS)
+    }
+
+    // Constructor for a solver that gives up after remembering `max_positions` positions
+    pub fn with_limit(max_positions: usize) -> Self {
+        Self {
+            memo: HashMap::new(),
+            max_positions,
+            time_limit: DEFAULT_TIME_LIMIT,
+            deadline: None,
+        }
+    }
+
+    // Finds the best move and the result it leads to for the player about to move
+    pub fn best_move(&mut self, game: &Game) -> Result<(Move, Outcome), SolveError> {
+        let moves = self.evaluate_moves(game)?;
+        // Reversed so the first of several equally good moves is picked
+        Ok(moves.into_iter().rev()
+            .max_by_key(|&(_, outcome)| outcome.score())
+            .expect("unfinished games always have a legal move"))
+    }
+
+    // Finds the result of every legal move for the player about to move
+    pub fn evaluate_moves(&mut self, game: &Game) -> Result<Vec<(Move, Outcome)>, SolveError> {
+        self.check(game)?;
+        self.deadline = Some(Instant::now() + self.time_limit);
+        game.legal_moves().into_iter()
+            .map(|mv| Ok((mv, self.outcome_after(game, mv)?)))
+            .collect()
+    }
+
+    // Checks the game is one the solver understands, and small enough to search
+    // Every empty tile ends up empty, x or o, so 3^empty is the most positions that can follow
+    pub fn check(&self, game: &Game) -> Result<(), SolveError> {
+        let empty = game.tiles().iter().flatten().filter(|tile| tile.is_none()).count();
+        let max_empty = (1..).take_while(|&tiles| 3usize.checked_pow(tiles).is_some_and(|positions| positions <= self.max_positions)).count();
+        if game.is_finished() {
+            Err(SolveError::GameAlreadyOver)
+        }
+        else if game.players().count() != 2 {
+            Err(SolveError::UnsupportedPlayers {count: game.players().count()})
+        }
+        else if empty > max_empty {
+            Err(SolveError::TooManyEmptyTiles {empty, max: max_empty})
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    // Finds the best result for the player about to move in an unfinished game
+    fn search(&mut self, game: &Game) -> Result<Outcome, SolveError> {
+        let key = position_key(game);
+        if let Some(&outcome) = self.memo.get(&key) {
+            return Ok(outcome);
+        }
+        else if self.memo.len() >= self.max_positions {
+            return Err(SolveError::TooLarge {max_positions: self.max_positions});
+        }
+        else if self.memo.len().is_multiple_of(CLOCK_CHECK_INTERVAL) && self.deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            return Err(SolveError::TimedOut {limit: self.time_limit});
+        }
+
+        let mut best: Option<Outcome> = None;
+        for mv in game.legal_moves() {
+            let outcome = self.outcome_after(game, mv)?;
+            if best.is_none_or(|best| outcome.score() > best.score()) {
+                best = Some(outcome);
+            }
+        }
+
+        let best = best.expect("unfinished games always have a legal move");
+        self.memo.insert(key, best);
+        Ok(best)
+    }
+
+    // Finds the result of a move for the player making it
+    fn outcome_after(&mut self, game: &Game, mv: Move) -> Result<Outcome, SolveError> {
+        let (piece, role) = (game.current_piece(), game.current_role());
+        let mut next = game.clone();
+        next.play(mv).expect("legal moves can always be played");
+
+        match next.winner() {
+            Some(winner) => Ok(match won_by(winner, piece, role) {
+                Some(true) => Outcome::Win(1),
+                Some(false) => Outcome::Loss(1),
+                None => Outcome::Draw,
+            }),
+            None => Ok(self.search(&next)?.flip()),
+        }
+    }
+}
+
+// Checks if the player with this piece or role is the winner, None for a tie
+pub fn won_by(winner: Winner, piece: Piece, role: Option<Role>) -> Option<bool> {
+    match winner {
+        Winner::Player(winning_piece) => Some(winning_piece == piece),
+        Winner::Order => Some(role == Some(Role::Order)),
+        Winner::Chaos => Some(role == Some(Role::Chaos)),
+        Winner::Tie => None,
+    }
+}
+
+// Packs the board and the player to move into bytes for the memo table
+fn position_key(game: &Game) -> Vec<u8> {
+    let mut key: Vec<u8> = game.tiles().iter()
+        .flat_map(|row| row.iter().map(|tile| tile.map_or(0, |piece| piece.index() as u8 + 1)))
+        .collect();
+    key.push(game.current_piece().index() as u8);
+    key.push(match game.current_role() {
+        Some(Role::Chaos) => 1,
+        _ => 0,
+    });
+    key
+}
+
+// Tests Below
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tic_tac_toe::game::{GameBuilder, Players};
+
+    fn game(board: &str) -> Game {
+        GameBuilder::new().board_string(board).unwrap().build().unwrap()
+    }
+
+    #[test]
+    fn solves_small_positions() {
+        assert_eq!(Solver::new().best_move(&game(".../.../...")).unwrap().1, Outcome::Draw);
+        let (mv, outcome) = Solver::new().best_move(&game("xx./oo./...")).unwrap();
+        assert_eq!((mv.row, mv.col, outcome), (0, 2, Outcome::Win(1)));
+        assert_eq!(Solver::new().best_move(&game("xx./oo./... o")).unwrap().1, Outcome::Win(1));
+    }
+
+    #[test]
+    fn refuses_games_it_cant_solve() {
+        assert_eq!(Solver::new().best_move(&game("xxx/oo./...")), Err(SolveError::GameAlreadyOver));
+
+        let three_players = GameBuilder::new().players(Players::new(3).unwrap()).build().unwrap();
+        assert_eq!(Solver::new().best_move(&three_players), Err(SolveError::UnsupportedPlayers {count: 3}));
+
+        // 3^16 possible boards is far more than the 2 million positions the solver remembers
+        let big = GameBuilder::new().size(4).build().unwrap();
+        assert_eq!(Solver::new().best_move(&big), Err(SolveError::TooManyEmptyTiles {empty: 16, max: 13}));
+        assert_eq!(Solver::with_limit(100).best_move(&game(".../.../...")), Err(SolveError::TooManyEmptyTiles {empty: 9, max: 4}));
+    }
+
+    #[test]
+    fn gives_up_after_the_time_limit() {
+        let game = game("xo../ox../..../....");
+        let mut solver = Solver::new();
+        solver.time_limit = Duration::ZERO;
+        assert_eq!(solver.best_move(&game), Err(SolveError::TimedOut {limit: Duration::ZERO}));
+    }
+}
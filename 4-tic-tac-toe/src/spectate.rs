@@ -0,0 +1,94 @@
+// Watches a game hosted by `serve`: the board is printed after every move, starting with the moves
+// played before the spectator joined. Spectators only listen, there is no way to send a move.
+
+// Standard Library Import Statements
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+// Package Import Statements
+use tic_tac_toe::game::{Players, Role, Tiles, EMPTY_SYMBOL};
+use serde_json::Value;
+use crate::i18n::t;
+use crate::{print_tiles, role_name};
+
+// Follows a game until it ends, e.g. "127.0.0.1:8080/games/1" (with or without "http://" in front)
+pub fn spectate(target: &str) -> Result<(), String> {
+    let target = target.trim_start_matches("http://");
+    let (addr, path) = match target.split_once('/') {
+        Some((addr, game)) if game.starts_with("games/") => (addr, format!("/{}/events", game.trim_end_matches('/'))),
+        _ => return Err(t!("spectate.target", target = target)),
+    };
+    let network_error = |err: std::io::Error| t!("spectate.lost", address = addr, error = err);
+
+    let mut stream = TcpStream::connect(addr).map_err(|err| t!("spectate.connect_failed", address = addr, error = err))?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, addr).map_err(network_error)?;
+    let mut lines = BufReader::new(stream).lines();
+
+    // The status line, then headers up to a blank line
+    let status = lines.next().transpose().map_err(network_error)?.unwrap_or_default();
+    while let Some(header) = lines.next().transpose().map_err(network_error)? {
+        if header.is_empty() {
+            break;
+        }
+    }
+    if status.split_whitespace().nth(1) != Some("200") {
+        let body = lines.next().transpose().map_err(network_error)?.unwrap_or_default();
+        let kind = serde_json::from_str::<Value>(&body).ok()
+            .and_then(|body| body.get("error")?.get("kind")?.as_str().map(String::from))
+            .unwrap_or(status);
+        return Err(t!("spectate.refused", target = target, error = kind));
+    }
+
+    // One position per line until the game ends
+    for line in lines {
+        let event = serde_json::from_str(&line.map_err(network_error)?).map_err(|err| t!("spectate.invalid_json", error = err))?;
+        if show_position(&event)? {
+            return Ok(());
+        }
+    }
+    println!("{}", t!("spectate.closed"));
+    Ok(())
+}
+
+// Prints one position the way players see it. Returns whether the game is over.
+fn show_position(event: &Value) -> Result<bool, String> {
+    let invalid = || t!("spectate.invalid_position");
+    let text = |key: &str| event.get(key).and_then(Value::as_str);
+    let strings = |key: &str| match event.get(key) {
+        Some(Value::Array(values)) => values.iter()
+            .map(|value| value.as_str().ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>(),
+        _ => Err(invalid()),
+    };
+
+    // Rebuild the board from the symbols, so it prints exactly like a local game
+    let symbols = strings("symbols")?.iter().map(|symbol| symbol.chars().next().ok_or_else(invalid)).collect::<Result<_, _>>()?;
+    let players = Players::with_symbols(symbols).map_err(|_| invalid())?;
+    let tiles: Tiles = strings("board")?.iter().map(|row| {
+        row.chars().map(|symbol| match symbol {
+            EMPTY_SYMBOL => Ok(None),
+            symbol => players.piece_for(symbol).map(Some).ok_or_else(invalid),
+        }).collect()
+    }).collect::<Result<_, _>>()?;
+    if tiles.is_empty() {
+        return Err(invalid());
+    }
+
+    match (text("move"), event.get("number").map(ToString::to_string)) {
+        (Some(mv), Some(number)) => println!("{}", t!("spectate.move", number = number, move_name = mv)),
+        _ => println!("{}", t!("spectate.start")),
+    }
+    print_tiles(&tiles, &players);
+
+    match (text("winner"), text("role"), text("to_move")) {
+        (Some("tie"), _, _) => println!("{}", t!("result.tie")),
+        (Some("order"), _, _) => println!("{}", t!("result.wins", who = role_name(Role::Order))),
+        (Some("chaos"), _, _) => println!("{}", t!("result.wins", who = role_name(Role::Chaos))),
+        (Some(winner), _, _) => println!("{}", t!("result.wins", who = winner.to_uppercase())),
+        (None, Some("order"), _) => println!("{}\n", t!("spectate.role", role = role_name(Role::Order))),
+        (None, Some(_), _) => println!("{}\n", t!("spectate.role", role = role_name(Role::Chaos))),
+        (None, None, Some(piece)) => println!("{}\n", t!("turn.piece", piece = piece)),
+        (None, None, None) => return Err(invalid()),
+    }
+    Ok(text("winner").is_some())
+}
@@ -0,0 +1,61 @@
+// Rotations and reflections of a square board
+// Positions that are rotations or mirror images of each other play out the same way,
+// so they can share one "canonical" entry in a lookup table.
+
+// Package Import Statements
+use tic_tac_toe::game::{Move, Tiles};
+
+// The eight ways to rotate and flip a square (the identity, three rotations and four reflections)
+pub const TRANSFORMS: usize = 8;
+
+// Moves the tile at (row, col) on a `size` x `size` board by one of the eight transforms
+pub fn transform(transform: usize, row: usize, col: usize, size: usize) -> (usize, usize) {
+    let last = size - 1;
+    match transform {
+        0 => (row, col),               // Identity
+        1 => (col, last - row),        // Rotate 90 degrees clockwise
+        2 => (last - row, last - col), // Rotate 180 degrees
+        3 => (last - col, row),        // Rotate 270 degrees clockwise
+        4 => (row, last - col),        // Mirror left to right
+        5 => (col, row),               // Mirror along the left diagonal (\)
+        6 => (last - row, col),        // Mirror top to bottom
+        7 => (last - col, last - row), // Mirror along the right diagonal (/)
+        _ => panic!("there are only {} transforms", TRANSFORMS),
+    }
+}
+
+// The transform that undoes another one
+pub fn inverse(transform: usize) -> usize {
+    match transform {
+        1 => 3,
+        3 => 1,
+        other => other, // Everything else is its own inverse
+    }
+}
+
+// Moves a move's tile by a transform, keeping its piece
+pub fn transform_move(t: usize, mv: Move, size: usize) -> Move {
+    let (row, col) = transform(t, mv.row, mv.col, size);
+    Move {row, col, piece: mv.piece}
+}
+
+// Lists the tiles row by row as bytes (0 for empty, piece index + 1 otherwise), after a transform
+pub fn transformed_cells(tiles: &Tiles, t: usize) -> Vec<u8> {
+    let size = tiles.len();
+    let mut cells = vec![0; size * size];
+    for (row, tiles_row) in tiles.iter().enumerate() {
+        for (col, tile) in tiles_row.iter().enumerate() {
+            let (new_row, new_col) = transform(t, row, col, size);
+            cells[new_row * size + new_col] = tile.map_or(0, |piece| piece.index() as u8 + 1);
+        }
+    }
+    cells
+}
+
+// Finds the smallest of the eight transformed boards, and the transform that produces it
+pub fn canonical_cells(tiles: &Tiles) -> (Vec<u8>, usize) {
+    (0..TRANSFORMS)
+        .map(|t| (transformed_cells(tiles, t), t))
+        .min()
+        .expect("there is always at least one transform")
+}
@@ -0,0 +1,46 @@
+// Integration tests for accessible output, for players using screen readers
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Plays with the given input and environment variables, then closes stdin
+fn run(args: &[&str], env: &[(&str, &str)], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+        .args(args)
+        .env_remove("TTT_ACCESSIBLE")
+        .env("LC_ALL", "C") // The sentences checked are the English ones
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game starts");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn boards_moves_and_results_are_sentences() {
+    let output = run(&["--accessible"], &[], "1A\nmoves\n2A\n1B\n2B\n1C\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+
+    assert!(!stdout.contains('\u{25A2}'), "{}", stdout);
+    assert!(stdout.contains("Row 1: X, empty, empty\nRow 2: empty, empty, empty\n"), "{}", stdout);
+    assert!(stdout.contains("Player X placed X on row 1, column A.\n"), "{}", stdout);
+    assert!(stdout.contains("You can play on 8 free tiles: row 1, column B; row 1, column C;"), "{}", stdout);
+    assert!(stdout.ends_with("Player X wins the game with 3 in a row, from row 1, column A to row 1, column C.\n"), "{}", stdout);
+
+    // Every prompt finishes its line
+    assert!(stdout.lines().filter(|line| line.starts_with("Enter move")).all(|line| line.ends_with("free tiles:")));
+}
+
+#[test]
+fn environment_variable_turns_it_on() {
+    let output = run(&["--bot", "o"], &[("TTT_ACCESSIBLE", "1")], "2B\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("The computer placed O on"), "{}", stdout);
+
+    let output = run(&[], &[("TTT_ACCESSIBLE", "0")], "2B\n");
+    assert!(String::from_utf8(output.stdout).unwrap().contains('\u{25A2}'));
+}
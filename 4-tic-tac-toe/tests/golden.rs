@@ -0,0 +1,254 @@
+// End-to-end tests that play the binary from a script and compare everything it prints with a stored transcript.
+//
+// Every tests/golden/<name>.case file is one run of the program:
+//
+//     # What the case is about (any number of comment lines)
+//     args: --size 4 --coach
+//     exit: 0
+//     --- stdin
+//     1A
+//     2B
+//     --- stdout
+//     ...
+//     --- stderr
+//     ...
+//
+// When the output changes on purpose, update the transcripts and review the diff before committing:
+//
+//     cargo test --test golden -- --bless
+//
+// A new case only needs its comments, args and stdin: --bless fills in the rest. Any other argument
+// picks which cases run, by part of their name. A missing newline at the very end of the output isn't
+// recorded, the transcripts always end their sections with one.
+
+// Standard Library Import Statements
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// One run of the program and what it printed
+#[derive(Debug, Default, PartialEq)]
+struct Case {
+    comments: Vec<String>,
+    args: String,
+    exit: Option<i32>,
+    stdin: String,
+    stdout: String,
+    stderr: String,
+}
+
+impl Case {
+    // Reads a .case file
+    fn parse(text: &str) -> Result<Case, String> {
+        let mut case = Case::default();
+        let mut section: Option<&mut String> = None;
+        for (number, line) in text.lines().enumerate() {
+            if let Some(name) = line.strip_prefix("--- ") {
+                section = Some(match name {
+                    "stdin" => &mut case.stdin,
+                    "stdout" => &mut case.stdout,
+                    "stderr" => &mut case.stderr,
+                    _ => return Err(format!("line {}: unknown section '{}'", number + 1, name)),
+                });
+                continue;
+            }
+            match &mut section {
+                Some(section) => {
+                    section.push_str(line);
+                    section.push('\n');
+                },
+                None if line.starts_with('#') => case.comments.push(line.to_string()),
+                None if line.trim().is_empty() => {},
+                None => match line.split_once(':') {
+                    Some(("args", args)) => case.args = args.trim().to_string(),
+                    Some(("exit", code)) => {
+                        case.exit = Some(code.trim().parse().map_err(|_| format!("line {}: invalid exit code", number + 1))?);
+                    },
+                    _ => return Err(format!("line {}: expected args:, exit: or a --- section", number + 1)),
+                },
+            }
+        }
+        Ok(case)
+    }
+
+    // Writes the case back out in the format parse() reads
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for comment in &self.comments {
+            text += comment;
+            text += "\n";
+        }
+        text += format!("args: {}", self.args).trim_end();
+        text += "\n";
+        if let Some(exit) = self.exit {
+            text += &format!("exit: {}\n", exit);
+        }
+        for (name, section) in [("stdin", &self.stdin), ("stdout", &self.stdout), ("stderr", &self.stderr)] {
+            text += &format!("--- {}\n{}", name, section);
+        }
+        text
+    }
+
+    // Plays the case's script and returns the same case with what the program actually printed
+    fn run(&self) -> Case {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+            .args(self.args.split_whitespace())
+            .env("LC_ALL", "C") // English messages, whatever the machine's language
+            .env_remove("TTT_ACCESSIBLE")
+            .env_remove("TTT_PLAYER")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("the game starts");
+        child.stdin.take().unwrap().write_all(self.stdin.as_bytes()).unwrap();
+
+        // A script that leaves the game waiting would otherwise hang the whole run
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while child.try_wait().unwrap().is_none() {
+            if Instant::now() > deadline {
+                let _ = child.kill();
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let output = child.wait_with_output().unwrap();
+
+        Case {
+            comments: self.comments.clone(),
+            args: self.args.clone(),
+            exit: output.status.code(),
+            stdin: self.stdin.clone(),
+            stdout: end_with_newline(String::from_utf8_lossy(&output.stdout).into_owned()),
+            stderr: end_with_newline(String::from_utf8_lossy(&output.stderr).into_owned()),
+        }
+    }
+}
+
+// Output is stored one line at a time, so the last line gets a newline like all the others
+fn end_with_newline(mut text: String) -> String {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+// Shows the lines that differ between two transcripts, - for expected and + for actual,
+// with a few unchanged lines around each change
+fn diff(expected: &str, actual: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+
+    // Longest common subsequence of lines, counted from the end
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+
+    const CONTEXT: usize = 3;
+    let changed: Vec<usize> = (0..lines.len()).filter(|&line| !lines[line].starts_with(' ')).collect();
+    let near_change = |line: usize| changed.iter().any(|&change| change.abs_diff(line) <= CONTEXT);
+    let mut shown = Vec::new();
+    for (number, line) in lines.iter().enumerate() {
+        if near_change(number) {
+            shown.push(line.as_str());
+        } else if near_change(number.saturating_sub(1)) || number == 0 {
+            shown.push("  ...");
+        }
+    }
+    shown.join("\n")
+}
+
+// Every case file, in name order
+fn case_files() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "case"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn main() {
+    // Flags meant for the standard test runner, like --nocapture, are ignored
+    let args: Vec<String> = env::args().skip(1).collect();
+    let bless = args.iter().any(|arg| arg == "--bless");
+    let filters: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+
+    let paths: Vec<PathBuf> = case_files().into_iter()
+        .filter(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            filters.is_empty() || filters.iter().any(|filter| name.contains(filter.as_str()))
+        })
+        .collect();
+    println!("\nrunning {} golden transcripts", paths.len());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let expected = match fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|text| Case::parse(&text)) {
+            Ok(case) => case,
+            Err(err) => {
+                println!("test {} ... FAILED", name);
+                failures.push(format!("{}: {}", path.display(), err));
+                continue;
+            },
+        };
+        let actual = expected.run();
+
+        if actual == expected {
+            println!("test {} ... ok", name);
+        } else if bless {
+            fs::write(path, actual.to_text()).unwrap_or_else(|err| panic!("Could not write {}: {}", path.display(), err));
+            println!("test {} ... blessed", name);
+        } else {
+            println!("test {} ... FAILED", name);
+            let mut report = format!("---- {} ----", name);
+            if actual.exit != expected.exit {
+                report += &format!("\nexit code: expected {:?}, got {:?}", expected.exit, actual.exit);
+            }
+            for (stream, expected, actual) in [("stdout", &expected.stdout, &actual.stdout), ("stderr", &expected.stderr, &actual.stderr)] {
+                if expected != actual {
+                    report += &format!("\n{}:\n{}", stream, diff(expected, actual));
+                }
+            }
+            failures.push(report);
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n\n{}\n", failures.join("\n\n"));
+        println!("If the new output is intended, update the transcripts with: cargo test --test golden -- --bless");
+    }
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!("\ntest result: {}. {} passed; {} failed\n", result, paths.len() - failures.len(), failures.len());
+    if !failures.is_empty() {
+        process::exit(101);
+    }
+}
@@ -0,0 +1,82 @@
+// Integration tests for playing in another language, picked with --lang or from the environment
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Plays with the given input and locale variables, then closes stdin
+fn run(args: &[&str], env: &[(&str, &str)], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+        .args(args)
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG")
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game starts");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn lang_flag_picks_the_language() {
+    let output = run(&["--lang", "es"], &[], "1A\n2A\n1B\n2B\n1C\n");
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Escribe tu movimiento (p. ej. 1A):"), "{}", stdout(&output));
+    assert!(stdout(&output).contains("¡Gana X!"), "{}", stdout(&output));
+
+    // --lang wins over the environment, and works after the subcommand's own arguments too
+    let output = run(&["--size", "3", "--lang", "en"], &[("LANG", "es_ES.UTF-8")], "1A\n2A\n1B\n2B\n1C\n");
+    assert!(stdout(&output).contains("X wins!"), "{}", stdout(&output));
+}
+
+#[test]
+fn environment_picks_the_language() {
+    let output = run(&[], &[("LANG", "es_ES.UTF-8")], "");
+    assert!(stdout(&output).contains("Ficha actual: x"), "{}", stdout(&output));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("La entrada se ha terminado"));
+
+    // LC_ALL comes before LANG, and languages without a catalog fall back to English
+    let output = run(&[], &[("LANG", "es_ES.UTF-8"), ("LC_ALL", "C")], "");
+    assert!(stdout(&output).contains("Current piece: x"), "{}", stdout(&output));
+    let output = run(&[], &[("LANG", "fr_FR.UTF-8")], "");
+    assert!(stdout(&output).contains("Current piece: x"), "{}", stdout(&output));
+}
+
+#[test]
+fn counts_use_the_right_plural() {
+    let output = run(&["--lang", "es"], &[], "2B\n");
+    assert!(stdout(&output).contains("Partida detenida tras 1 movimiento."), "{}", stdout(&output));
+    let output = run(&["--lang", "es"], &[], "2B\n1A\n");
+    assert!(stdout(&output).contains("Partida detenida tras 2 movimientos."), "{}", stdout(&output));
+    let output = run(&["--lang", "en"], &[], "2B\n");
+    assert!(stdout(&output).contains("Game stopped after 1 move."), "{}", stdout(&output));
+}
+
+#[test]
+fn errors_and_descriptions_are_translated() {
+    let output = run(&["--lang", "es"], &[], "2B\n2B\nZ9\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("¡La casilla 2B ya tiene la ficha x!"), "{}", stderr);
+    assert!(stderr.contains("Movimiento no válido 'Z9': la fila 9 está fuera del tablero"), "{}", stderr);
+
+    let output = run(&["--lang", "es", "--players", "1"], &[], "");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Una partida necesita al menos 2 jugadores, no 1"));
+
+    let output = run(&["--lang", "es", "--accessible"], &[], "2B\n");
+    assert!(stdout(&output).contains("Fila 1: vacía, vacía, vacía"), "{}", stdout(&output));
+    assert!(stdout(&output).contains("en fila 2, columna B."), "{}", stdout(&output));
+}
+
+#[test]
+fn unknown_languages_are_rejected() {
+    let output = run(&["--lang", "xx"], &[], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown language 'xx', expected one of: en, es"));
+}
@@ -0,0 +1,103 @@
+// Integration tests for the --json machine interface, driving the binary one command per line
+
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+// A running game, with a line of JSON out for every line of JSON in
+struct Machine {
+    child: Child,
+    replies: Lines<BufReader<ChildStdout>>,
+}
+
+impl Machine {
+    // Starts the game and reads the reply describing it
+    fn start(args: &[&str]) -> (Self, String) {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+            .arg("--json")
+            .args(args)
+            .env("LC_ALL", "C")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("the game starts");
+        let replies = BufReader::new(child.stdout.take().unwrap()).lines();
+        let mut machine = Machine {child, replies};
+        let first = machine.reply();
+        (machine, first)
+    }
+
+    // Sends one command and reads its reply
+    fn send(&mut self, command: &str) -> String {
+        writeln!(self.child.stdin.as_mut().unwrap(), "{}", command).unwrap();
+        self.reply()
+    }
+
+    fn reply(&mut self) -> String {
+        self.replies.next().expect("every command gets a reply").unwrap()
+    }
+}
+
+impl Drop for Machine {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn commands_get_the_state_back() {
+    let (mut machine, start) = Machine::start(&[]);
+    assert!(start.contains("\"to_move\":\"x\""), "{}", start);
+    assert!(start.contains("\"clocks_ms\":null"), "{}", start);
+
+    let reply = machine.send("{\"move\":\"2B\"}");
+    assert!(reply.contains("\"board\":[\"...\",\".x.\",\"...\"]"), "{}", reply);
+    assert!(!reply.contains("\"error\""), "{}", reply);
+
+    let reply = machine.send("{\"move\":\"2B\"}");
+    assert!(reply.contains("\"error\":{\"kind\":\"TileNotEmpty\""), "{}", reply);
+
+    let reply = machine.send("{\"undo\":true}");
+    assert!(reply.contains("\"history\":[]"), "{}", reply);
+}
+
+#[test]
+fn timed_games_end_when_a_clock_runs_out() {
+    let (mut machine, start) = Machine::start(&["--time", "1+5"]);
+    assert!(start.contains("\"clocks_ms\":["), "{}", start);
+
+    let reply = machine.send("{\"move\":\"2B\"}");
+    assert!(!reply.contains("\"error\""), "{}", reply);
+
+    // Moves can't be taken back, since that would hand the time back as well
+    let reply = machine.send("{\"undo\":true}");
+    assert!(reply.contains("\"kind\":\"UndoNotAllowed\""), "{}", reply);
+
+    // o lets their second run out, so their move comes too late and x wins
+    thread::sleep(Duration::from_millis(1_500));
+    let reply = machine.send("{\"move\":\"1A\"}");
+    assert!(reply.contains("\"kind\":\"GameAlreadyOver\""), "{}", reply);
+    assert!(reply.contains("\"winner\":\"x\""), "{}", reply);
+    assert!(reply.contains("\"clocks_ms\":["), "{}", reply);
+    assert!(reply.contains(",0]"), "{}", reply);
+}
+
+#[test]
+fn malformed_commands_get_an_error_reply() {
+    let (mut machine, _) = Machine::start(&[]);
+    for command in ["[1,", "{\"move\" \"2B\"}", "tru", "{\"move\":\"2B\"} 2", &"[".repeat(100_000)] {
+        let reply = machine.send(command);
+        assert!(reply.contains("\"error\":{\"kind\":\"InvalidJson\",\"message\":"), "{}", reply);
+        assert!(reply.contains("\"history\":[]"), "{}", reply);
+    }
+
+    // Escapes are read like any other JSON, and quotes in the input are escaped in the reply
+    let reply = machine.send("{\"move\":\"\\u0032\\u0042\"}");
+    assert!(reply.contains("\"history\":[\"2B\"]"), "{}", reply);
+    let reply = machine.send("{\"move\":\"\\\"9Z\"}");
+    assert!(reply.contains("\"kind\":\"InvalidMove\",\"input\":\"\\\"9Z\""), "{}", reply);
+    let reply = machine.send("{\"move\":2}");
+    assert!(reply.contains("\"kind\":\"InvalidCommand\""), "{}", reply);
+}
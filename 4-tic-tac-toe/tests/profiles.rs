@@ -0,0 +1,64 @@
+// Integration tests for the names players type in a match and the profiles they are rated under
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Output, Stdio};
+
+// A data directory of its own, so the tests never touch the real profiles
+fn data_home(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("tic-tac-toe-profiles-test-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+// Plays with the given input, then closes stdin
+fn run(args: &[&str], data_home: &Path, input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+        .args(args)
+        .env("LC_ALL", "C")
+        .env("XDG_DATA_HOME", data_home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game starts");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+// The names with a profile in the data file
+fn profile_names(data_home: &Path) -> Vec<String> {
+    let contents = fs::read_to_string(data_home.join("tic-tac-toe").join("profiles.tsv")).unwrap_or_default();
+    contents.lines()
+        .filter_map(|line| line.strip_prefix("player\t"))
+        .map(|fields| fields.split('\t').next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn typed_names_must_differ() {
+    let dir = data_home("typed");
+    let output = run(&["--best-of", "1"], &dir, "Alice\nAlice\nBob\n1A\n2A\n1B\n2B\n1C\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Alice is already playing, please choose another name"));
+    assert_eq!(profile_names(&dir), ["Alice", "Bob"]);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn stand_ins_and_the_computer_have_no_profile() {
+    // Nobody typed a name for the second player, so there is nobody to rate Alice against
+    let dir = data_home("stand-in");
+    let output = run(&["--best-of", "1"], &dir, "Alice\n\n1A\n2A\n1B\n2B\n1C\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Alice wins the match!"));
+    assert!(profile_names(&dir).is_empty());
+
+    // The same goes for the computer, even when it is named with --names
+    let output = run(&["--names", "Alice,Robot", "--bot", "o"], &dir, "1A\n1B\n2B\n3A\n3C\n2A\n");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(profile_names(&dir).is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
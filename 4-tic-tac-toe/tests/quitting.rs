@@ -0,0 +1,129 @@
+// Integration tests for quitting the interactive game early: input that runs out mid-game and Ctrl-C
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Exit codes for the ways a game can stop
+const EXIT_END_OF_INPUT: i32 = 3;
+const EXIT_INTERRUPTED: i32 = 130;
+
+// Starts the game with its stdin, stdout and stderr piped
+fn spawn(args: &[&str]) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+        .args(args)
+        .env("LC_ALL", "C") // The messages checked are the English ones
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game starts")
+}
+
+// Waits for the game to exit, failing the test instead of hanging if it never does
+fn finish(mut child: Child) -> Output {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while child.try_wait().unwrap().is_none() {
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            panic!("the game kept running after its input ended");
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    child.wait_with_output().unwrap()
+}
+
+// Plays with the given input, then closes stdin
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = spawn(args);
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    finish(child)
+}
+
+// Reads stdout until `text` has been printed `times` times, returning everything read so far
+fn wait_for(child: &mut Child, text: &str, times: usize) -> String {
+    let stdout = child.stdout.as_mut().unwrap();
+    let mut seen = Vec::new();
+    let mut byte = [0];
+    while String::from_utf8_lossy(&seen).matches(text).count() < times {
+        assert_eq!(stdout.read(&mut byte).unwrap(), 1, "stdout closed before '{}'", text);
+        seen.push(byte[0]);
+    }
+    String::from_utf8(seen).unwrap()
+}
+
+// A file name in the temp directory no other test uses
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("tic-tac-toe-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn finished_games_exit_cleanly() {
+    let output = run(&[], "1A\n2A\n1B\n2B\n1C\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("X wins!"));
+}
+
+#[test]
+fn input_ending_mid_game_prints_a_summary() {
+    let output = run(&[], "2B\n1A\n");
+    assert_eq!(output.status.code(), Some(EXIT_END_OF_INPUT));
+    assert!(stdout(&output).contains("Game stopped after 2 moves."), "{}", stdout(&output));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("The input ended"));
+}
+
+#[test]
+fn input_ending_at_any_prompt_stops_the_game() {
+    // Before the first move, at the coach's question, while naming players and during puzzles
+    assert_eq!(run(&[], "").status.code(), Some(EXIT_END_OF_INPUT));
+    assert_eq!(run(&["--coach"], "2B\n1B\n").status.code(), Some(EXIT_END_OF_INPUT));
+    assert_eq!(run(&["--best-of", "3"], "Alice\n").status.code(), Some(EXIT_END_OF_INPUT));
+
+    let output = run(&["--best-of", "3", "--names", "Alice,Bob"], "1A\n2B\n");
+    assert_eq!(output.status.code(), Some(EXIT_END_OF_INPUT));
+    assert!(stdout(&output).contains("Score: "), "{}", stdout(&output));
+
+    let output = run(&["puzzle", "--count", "1"], "");
+    assert_eq!(output.status.code(), Some(EXIT_END_OF_INPUT));
+    assert!(stdout(&output).contains("You solved 0 of 0 puzzles."), "{}", stdout(&output));
+}
+
+#[test]
+fn ctrl_c_offers_to_save_the_game() {
+    let path = temp_path("saved.ttt");
+    let mut child = spawn(&["--names", "Alice,Bob"]);
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "2B").unwrap();
+    wait_for(&mut child, "Enter move", 2);
+
+    let status = Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    wait_for(&mut child, "Enter a file name", 1);
+    writeln!(stdin, "{}", path.display()).unwrap();
+    drop(stdin);
+
+    let output = finish(child);
+    assert_eq!(output.status.code(), Some(EXIT_INTERRUPTED));
+
+    // The saved game carries on as a correspondence game
+    let saved = fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("player\tAlice\nplayer\tBob\nmove\tAlice\t2B\t"), "{}", saved);
+    let output = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+        .args(["move", path.to_str().unwrap(), "1A", "--as", "Bob"])
+        .env("LC_ALL", "C")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout(&output).contains("Alice to move"), "{}", stdout(&output));
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(path.with_extension("ttt.lock"));
+}
@@ -0,0 +1,144 @@
+// Round trips through the `serde` feature, which is on by default
+#![cfg(feature = "serde")]
+
+use tic_tac_toe::{Game, GameBuilder, Move, MoveError, Piece, Players, Variant, Winner};
+
+// Saves a value as JSON and reads it back
+fn json_round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+// Saves a value with bincode and reads it back
+fn bincode_round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+}
+
+// Checks two games are the same position with the same moves played
+fn assert_same_game(left: &Game, right: &Game) {
+    assert_eq!(left.to_board_string(), right.to_board_string());
+    assert_eq!(left.history(), right.history());
+    assert_eq!(left.winner(), right.winner());
+    assert_eq!(left.variant(), right.variant());
+    assert_eq!(left.win_length(), right.win_length());
+    assert_eq!(left.players(), right.players());
+    assert_eq!(left.current_role(), right.current_role());
+}
+
+#[test]
+fn small_types_round_trip() {
+    let winners = [Winner::Player(Piece::O), Winner::Order, Winner::Chaos, Winner::Tie];
+    let errors = [
+        MoveError::GameAlreadyOver,
+        MoveError::InvalidPosition {row: 3, col: 0},
+        MoveError::TileNotEmpty {other_piece: Piece::X, row: 1, col: 2},
+        MoveError::WrongPiece {piece: Piece::new(2)},
+        MoveError::PieceRequired,
+    ];
+
+    assert_eq!(json_round_trip(&Piece::new(5)), Piece::new(5));
+    assert_eq!(bincode_round_trip(&Piece::new(5)), Piece::new(5));
+    for winner in &winners {
+        assert_eq!(&json_round_trip(winner), winner);
+        assert_eq!(&bincode_round_trip(winner), winner);
+    }
+    for err in &errors {
+        assert_eq!(&json_round_trip(err), err);
+        assert_eq!(&bincode_round_trip(err), err);
+    }
+}
+
+#[test]
+fn games_round_trip() {
+    // A game in progress, a finished one and an Order and Chaos game with pieces placed up front
+    let mut in_progress = GameBuilder::new()
+        .players(Players::with_symbols(vec!['a', 'b', 'c']).unwrap())
+        .size(4)
+        .starting_piece(Piece::new(1))
+        .build()
+        .unwrap();
+    in_progress.make_move(0, 0).unwrap();
+    in_progress.make_move(3, 3).unwrap();
+
+    let mut finished = Game::new();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        finished.make_move(row, col).unwrap();
+    }
+
+    let mut order_chaos = GameBuilder::new().variant(Variant::OrderAndChaos).place(2, 2, Piece::O).build().unwrap();
+    order_chaos.play(Move {row: 0, col: 5, piece: Piece::X}).unwrap();
+
+    for game in &[in_progress, finished, order_chaos] {
+        assert_same_game(&json_round_trip(game), game);
+        assert_same_game(&bincode_round_trip(game), game);
+    }
+}
+
+#[test]
+fn loaded_games_keep_playing() {
+    let mut game = Game::new();
+    game.make_move(1, 1).unwrap();
+    let mut loaded = bincode_round_trip(&game);
+
+    assert_eq!(loaded.current_piece(), Piece::O);
+    assert!(matches!(loaded.make_move(1, 1), Err(MoveError::TileNotEmpty {other_piece: Piece::X, row: 1, col: 1})));
+    loaded.make_move(0, 0).unwrap();
+    assert_eq!(loaded.undo(), Some(Move {row: 0, col: 0, piece: Piece::O}));
+    assert_eq!(loaded.undo(), Some(Move {row: 1, col: 1, piece: Piece::X}));
+}
+
+#[test]
+fn winner_is_worked_out_not_trusted() {
+    let mut game = Game::new();
+    for &(row, col) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+        game.make_move(row, col).unwrap();
+    }
+    let json = serde_json::to_value(&game).unwrap();
+    assert!(json.get("winner").is_none(), "{}", json);
+
+    // Taking x's winning piece off the board reopens the game
+    let mut edited = json.clone();
+    edited["tiles"][0][2] = serde_json::Value::Null;
+    edited["history"].as_array_mut().unwrap().pop();
+    let loaded: Game = serde_json::from_value(edited).unwrap();
+    assert_eq!(loaded.winner(), None);
+
+    // Who ran out of time is saved, so a game won on time stays won
+    let mut timed_out = Game::new();
+    timed_out.time_out().unwrap();
+    assert_eq!(json_round_trip(&timed_out).winner(), Some(Winner::Player(Piece::O)));
+
+    // and a player who ran out in a three player game stays out of the turn order
+    let mut three = Game::with_rules(Variant::Standard, 4, 3, Players::new(3).unwrap()).unwrap();
+    three.time_out().unwrap();
+    let mut loaded = bincode_round_trip(&three);
+    assert_eq!((loaded.winner(), loaded.timed_out()), (None, &[Piece::X][..]));
+    loaded.make_move(0, 0).unwrap();
+    loaded.make_move(0, 1).unwrap();
+    assert_eq!(loaded.current_piece(), Piece::O);
+}
+
+#[test]
+fn invalid_games_are_rejected() {
+    let mut game = Game::new();
+    game.make_move(1, 1).unwrap();
+    let json = serde_json::to_value(&game).unwrap();
+    let load = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut edited = json.clone();
+        edit(&mut edited);
+        serde_json::from_value::<Game>(edited)
+    };
+
+    assert!(load(&|_| {}).is_ok());
+    assert!(load(&|game| game["tiles"][0] = serde_json::json!([null, null])).is_err()); // Not square
+    assert!(load(&|game| game["tiles"][0][0] = serde_json::json!(7)).is_err()); // Nobody's piece
+    assert!(load(&|game| game["to_move"] = serde_json::json!(2)).is_err());
+    assert!(load(&|game| game["history"][0]["col"] = serde_json::json!(0)).is_err()); // Not on the board
+    assert!(load(&|game| game["win_length"] = serde_json::json!(4)).is_err());
+    assert!(load(&|game| game["players"]["symbols"] = serde_json::json!(["x", "x"])).is_err());
+
+    // Two players can't both have a line
+    assert!(load(&|game| {
+        game["tiles"] = serde_json::json!([[0, 0, 0], [1, 1, 1], [null, null, null]]);
+        game["history"] = serde_json::json!([]);
+    }).is_err());
+}
@@ -0,0 +1,269 @@
+// Integration tests for the `serve` subcommand, talking HTTP to the binary over a loopback port
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+// A running server, stopped when the test is done with it
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    // Starts the server on a free port and waits until it is listening
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tic-tac-toe"))
+            .args(["serve", "--addr", "127.0.0.1:0"])
+            .env("LC_ALL", "C") // The address is read from the English message
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("the server starts");
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut line).unwrap();
+        let addr = line.trim().strip_prefix("Listening on http://").expect("the server prints its address").to_string();
+        Server {child, addr}
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// Sends one request and returns the status code and body
+fn request(addr: &str, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", method, path, body.len(), body).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = response.split_once("\r\n\r\n").unwrap().1.trim().to_string();
+    (status, body)
+}
+
+// Starts a game and returns its id
+fn create_game(addr: &str, settings: &str) -> String {
+    let (status, body) = request(addr, "POST", "/games", settings);
+    assert_eq!(status, 201, "{}", body);
+    let id = body.strip_prefix("{\"id\":").unwrap();
+    id[..id.find(',').unwrap()].to_string()
+}
+
+#[test]
+fn game_lifecycle() {
+    let server = Server::start();
+    let id = create_game(&server.addr, "");
+
+    let (status, body) = request(&server.addr, "POST", &format!("/games/{}/moves", id), "{\"move\":\"2B\"}");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"board\":[\"...\",\".x.\",\"...\"]"), "{}", body);
+    assert!(body.contains("\"to_move\":\"o\""), "{}", body);
+
+    let (status, body) = request(&server.addr, "GET", &format!("/games/{}", id), "");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"history\":[\"2B\"]"), "{}", body);
+
+    assert_eq!(request(&server.addr, "DELETE", &format!("/games/{}", id), "").0, 204);
+    assert_eq!(request(&server.addr, "GET", &format!("/games/{}", id), "").0, 404);
+    assert_eq!(request(&server.addr, "DELETE", &format!("/games/{}", id), "").0, 404);
+}
+
+#[test]
+fn finished_game_reports_winner() {
+    let server = Server::start();
+    let id = create_game(&server.addr, "");
+    for mv in ["1A", "2A", "1B", "2B", "1C"] {
+        assert_eq!(request(&server.addr, "POST", &format!("/games/{}/moves", id), &format!("{{\"move\":\"{}\"}}", mv)).0, 200);
+    }
+
+    let (_, body) = request(&server.addr, "GET", &format!("/games/{}", id), "");
+    assert!(body.contains("\"winner\":\"x\""), "{}", body);
+    assert!(body.contains("\"legal_moves\":[]"), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", &format!("/games/{}/moves", id), "{\"move\":\"3C\"}");
+    assert_eq!(status, 409);
+    assert!(body.contains("\"kind\":\"GameAlreadyOver\""), "{}", body);
+}
+
+#[test]
+fn move_errors_map_to_client_errors() {
+    let server = Server::start();
+    let id = create_game(&server.addr, "");
+    let moves = format!("/games/{}/moves", id);
+    request(&server.addr, "POST", &moves, "{\"move\":\"2B\"}");
+
+    let (status, body) = request(&server.addr, "POST", &moves, "{\"move\":\"2B\"}");
+    assert_eq!(status, 409);
+    assert!(body.contains("\"error\":{\"kind\":\"TileNotEmpty\",\"row\":1,\"col\":1,\"tile\":\"2B\",\"other_piece\":\"x\"}"), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", &moves, "{\"move\":\"1A x\"}");
+    assert_eq!(status, 422);
+    assert!(body.contains("\"kind\":\"WrongPiece\""), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", &moves, "{\"move\":\"9Z\"}");
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidMove\""), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", &moves, "not json");
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidJson\""), "{}", body);
+
+    // Order and Chaos players have to say which piece they place
+    let id = create_game(&server.addr, "{\"order_chaos\":true}");
+    let (status, body) = request(&server.addr, "POST", &format!("/games/{}/moves", id), "{\"move\":\"1A\"}");
+    assert_eq!(status, 422);
+    assert!(body.contains("\"kind\":\"PieceRequired\""), "{}", body);
+}
+
+#[test]
+fn invalid_requests() {
+    let server = Server::start();
+    assert_eq!(request(&server.addr, "GET", "/games/12345", "").0, 404);
+    assert_eq!(request(&server.addr, "GET", "/nowhere", "").0, 404);
+    assert_eq!(request(&server.addr, "PUT", "/games", "").0, 405);
+
+    let (status, body) = request(&server.addr, "POST", "/games", "{\"size\":0}");
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidSetup\""), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", "/games", "{\"colour\":\"blue\"}");
+    assert_eq!(status, 400);
+    assert!(body.contains("colour"), "{}", body);
+}
+
+#[test]
+fn deeply_nested_bodies_are_rejected() {
+    let server = Server::start();
+
+    // Well under the body size limit, but deep enough to overflow the stack of a parser without a depth limit
+    let (status, body) = request(&server.addr, "POST", "/games", &"[".repeat(60 * 1024));
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidJson\""), "{}", body);
+
+    let id = create_game(&server.addr, "");
+    let (status, body) = request(&server.addr, "POST", &format!("/games/{}/moves", id), &"{\"move\":".repeat(5_000));
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidJson\""), "{}", body);
+
+    // The server is still up
+    assert_eq!(request(&server.addr, "GET", &format!("/games/{}", id), "").0, 200);
+}
+
+#[test]
+fn custom_setup() {
+    let server = Server::start();
+    let (status, body) = request(&server.addr, "POST", "/games", "{\"size\":4,\"win\":3,\"players\":3,\"place\":[\"2B:+\"]}");
+    assert_eq!(status, 201);
+    assert!(body.contains("\"board\":[\"....\",\".+..\",\"....\",\"....\"]"), "{}", body);
+}
+
+#[test]
+fn bot_replies_to_moves() {
+    let server = Server::start();
+
+    // The computer moving first has already played when the game is created
+    let (status, body) = request(&server.addr, "POST", "/games", "{\"bot\":\"x\"}");
+    assert_eq!(status, 201);
+    assert!(body.contains("\"to_move\":\"o\""), "{}", body);
+
+    let id = create_game(&server.addr, "{\"bot\":\"o\"}");
+    let (status, body) = request(&server.addr, "POST", &format!("/games/{}/moves", id), "{\"move\":\"1A\"}");
+    assert_eq!(status, 200);
+    assert!(body.contains("\"history\":[\"1A\",\"2B\"]"), "{}", body);
+    assert!(body.contains("\"to_move\":\"x\""), "{}", body);
+}
+
+#[test]
+fn bots_are_refused_on_boards_too_big_to_search() {
+    let server = Server::start();
+    for settings in ["{\"order_chaos\":true,\"bot\":\"chaos\"}", "{\"size\":4,\"bot\":\"o\"}"] {
+        let (status, body) = request(&server.addr, "POST", "/games", settings);
+        assert_eq!(status, 400, "{}", body);
+        assert!(body.contains("\"kind\":\"BotUnsupported\""), "{}", body);
+    }
+}
+
+#[test]
+fn timed_games_end_when_a_clock_runs_out() {
+    let server = Server::start();
+    let id = create_game(&server.addr, "{\"time\":\"1+5\"}");
+    let moves = format!("/games/{}/moves", id);
+
+    // Moving in time earns the increment
+    let (status, body) = request(&server.addr, "POST", &moves, "{\"move\":\"2B\"}");
+    assert_eq!(status, 200);
+    let clocks = body.split("\"clocks_ms\":[").nth(1).expect("timed games report their clocks");
+    let x_left: u64 = clocks[..clocks.find(',').unwrap()].parse().unwrap();
+    assert!(x_left > 5_000, "{}", body);
+
+    // o lets their second run out, so their move comes too late and x wins
+    thread::sleep(Duration::from_millis(1_500));
+    let (status, body) = request(&server.addr, "POST", &moves, "{\"move\":\"1A\"}");
+    assert_eq!(status, 409);
+    assert!(body.contains("\"kind\":\"GameAlreadyOver\""), "{}", body);
+    assert!(body.contains("\"winner\":\"x\""), "{}", body);
+    assert!(body.contains("\"history\":[\"2B\"]"), "{}", body);
+
+    let (status, body) = request(&server.addr, "POST", "/games", "{\"time\":\"soon\"}");
+    assert_eq!(status, 400);
+    assert!(body.contains("\"kind\":\"InvalidSetup\""), "{}", body);
+}
+
+#[test]
+fn concurrent_games_stay_separate() {
+    let server = Server::start();
+
+    // Every thread plays its own game, each starting on a different tile
+    let handles: Vec<_> = ["1A", "1B", "1C", "2A", "2B", "2C", "3A", "3B"].iter().map(|&mv| {
+        let addr = server.addr.clone();
+        thread::spawn(move || {
+            let id = create_game(&addr, "");
+            let (status, body) = request(&addr, "POST", &format!("/games/{}/moves", id), &format!("{{\"move\":\"{}\"}}", mv));
+            assert_eq!(status, 200);
+            assert!(body.contains(&format!("\"history\":[\"{}\"]", mv)), "{}", body);
+            id
+        })
+    }).collect();
+
+    let mut ids: Vec<String> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 8);
+}
+
+#[test]
+fn spectators_catch_up_then_follow_moves() {
+    let server = Server::start();
+    let id = create_game(&server.addr, "");
+    let moves = format!("/games/{}/moves", id);
+    request(&server.addr, "POST", &moves, "{\"move\":\"1A\"}");
+
+    let mut stream = TcpStream::connect(&server.addr).unwrap();
+    write!(stream, "GET /games/{}/events HTTP/1.1\r\nHost: localhost\r\n\r\n", id).unwrap();
+    let mut lines = BufReader::new(stream).lines().map(Result::unwrap);
+    assert!(lines.next().unwrap().starts_with("HTTP/1.1 200"));
+    let mut lines = lines.skip_while(|line| !line.is_empty()).skip(1);
+
+    // The starting position and the move played before joining
+    assert!(lines.next().unwrap().starts_with("{\"number\":0,\"move\":null,"));
+    assert!(lines.next().unwrap().starts_with("{\"number\":1,\"move\":\"1A\","));
+
+    // Moves played after joining arrive as they happen, and the stream ends with the game
+    for mv in ["2A", "1B", "2B", "1C"] {
+        request(&server.addr, "POST", &moves, &format!("{{\"move\":\"{}\"}}", mv));
+    }
+    let rest: Vec<String> = lines.collect();
+    assert_eq!(rest.len(), 4);
+    assert!(rest[3].starts_with("{\"number\":5,\"move\":\"1C\","), "{}", rest[3]);
+    assert!(rest[3].contains("\"winner\":\"x\""), "{}", rest[3]);
+
+    // Spectators can't watch games that don't exist
+    assert_eq!(request(&server.addr, "GET", "/games/999/events", "").0, 404);
+}